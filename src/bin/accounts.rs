@@ -0,0 +1,130 @@
+use clap::Parser;
+use masm_project_template::{
+    common::{
+        account_transactions, emit_json, format_account_id, instantiate_client, list_accounts,
+        sync_state_retry,
+    },
+    prelude::*,
+};
+
+/// Lists the accounts in the local store and their transaction history
+#[derive(Parser)]
+struct Args {
+    /// Print the accounts as one JSON array instead
+    #[arg(long)]
+    json: bool,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let json = Args::parse().json;
+
+    // -------------------------------------------------------------------------
+    // Instantiate client (keeps the existing store)
+    // -------------------------------------------------------------------------
+    let endpoint = Endpoint::testnet();
+    let mut client =
+        instantiate_client(endpoint, StoreBackend::default(), &RpcConfig::default()).await?;
+
+    let sync_summary = sync_state_retry(&mut client, 3).await?;
+    if !json {
+        println!("⛓  Latest block: {}", sync_summary.block_num);
+    }
+
+    // -------------------------------------------------------------------------
+    // List accounts in the store
+    // -------------------------------------------------------------------------
+    let accounts = list_accounts(&mut client).await?;
+
+    if json {
+        let mut entries = Vec::new();
+        for account in &accounts {
+            let balances: Vec<_> = account
+                .balances
+                .iter()
+                .map(|(faucet_id, amount)| {
+                    serde_json::json!({
+                        "faucet_id": format_account_id(*faucet_id, Network::Testnet),
+                        "amount": amount,
+                    })
+                })
+                .collect();
+            let transactions: Vec<_> = account_transactions(&mut client, account.id)
+                .await?
+                .into_iter()
+                .map(|record| {
+                    serde_json::json!({
+                        "id": record.id.to_hex(),
+                        "block_num": record.details.block_num.as_u32(),
+                        "status": record.status.to_string(),
+                    })
+                })
+                .collect();
+
+            entries.push(serde_json::json!({
+                "id": format_account_id(account.id, Network::Testnet),
+                "type": format!("{:?}", account.account_type),
+                "storage": account.storage_mode.to_string(),
+                "nonce": account.nonce,
+                "balances": balances,
+                "transactions": transactions,
+            }));
+        }
+        emit_json(entries)?;
+        return Ok(());
+    }
+
+    if accounts.is_empty() {
+        println!("No accounts in the store.");
+        return Ok(());
+    }
+
+    println!(
+        "{:<40} {:<30} {:<8} {:>6}  balances",
+        "account id", "type", "storage", "nonce"
+    );
+    for account in &accounts {
+        let balances: Vec<String> = account
+            .balances
+            .iter()
+            .map(|(faucet_id, amount)| {
+                format!(
+                    "{} {}",
+                    amount,
+                    format_account_id(*faucet_id, Network::Testnet)
+                )
+            })
+            .collect();
+
+        println!(
+            "{:<40} {:<30} {:<8} {:>6}  {}",
+            format_account_id(account.id, Network::Testnet),
+            format!("{:?}", account.account_type),
+            account.storage_mode.to_string(),
+            account.nonce,
+            balances.join(", ")
+        );
+    }
+
+    // -------------------------------------------------------------------------
+    // Transaction history per account
+    // -------------------------------------------------------------------------
+    for account in &accounts {
+        let records = account_transactions(&mut client, account.id).await?;
+        if records.is_empty() {
+            continue;
+        }
+
+        println!("\n{}", format_account_id(account.id, Network::Testnet));
+        for record in records {
+            println!(
+                "  {} block {} {}",
+                record.id.to_hex(),
+                record.details.block_num,
+                record.status
+            );
+        }
+    }
+
+    Ok(())
+}
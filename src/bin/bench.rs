@@ -0,0 +1,82 @@
+use std::{path::Path, time::Duration};
+
+use clap::Parser;
+use masm_project_template::{
+    common::{
+        bench_increment, check_endpoint, delete_keystore_and_store, emit_json, format_account_id,
+        import_account_by_id_waiting, instantiate_client, parse_connectable_network,
+        resolve_counter_id, sync_state_retry,
+    },
+    prelude::*,
+};
+
+/// Increments a deployed counter contract repeatedly and reports the throughput
+#[derive(Parser)]
+struct Args {
+    /// Counter to increment; defaults to the one in ./counter_deployment.json for the chosen
+    /// network, then (on testnet only) the template's testnet counter
+    #[arg(long)]
+    account_id: Option<String>,
+
+    /// Network to connect to: testnet, devnet or localhost (mainnet has no endpoint yet)
+    #[arg(long, default_value = "testnet", value_parser = parse_connectable_network)]
+    network: Network,
+
+    /// Number of sequential increments to submit
+    #[arg(long, default_value_t = 10)]
+    count: usize,
+
+    /// Print the results as one JSON object instead
+    #[arg(long)]
+    json: bool,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::parse();
+    let network = args.network;
+
+    delete_keystore_and_store().await;
+
+    let endpoint = network
+        .endpoint()
+        .ok_or_else(|| format!("no RPC endpoint known for {:?}", network))?;
+    check_endpoint(&endpoint, 10_000).await?;
+    let mut client =
+        instantiate_client(endpoint, StoreBackend::default(), &RpcConfig::default()).await?;
+    sync_state_retry(&mut client, 3).await?;
+
+    let counter_contract_id = resolve_counter_id(
+        args.account_id.as_deref(),
+        network,
+        Path::new("./counter_deployment.json"),
+    )?;
+    import_account_by_id_waiting(&mut client, counter_contract_id, Duration::from_secs(30)).await?;
+
+    if !args.json {
+        println!(
+            "⏱  Submitting {} increments to {} …",
+            args.count,
+            format_account_id(counter_contract_id, network)
+        );
+    }
+    let report = bench_increment(&mut client, counter_contract_id, args.count).await?;
+
+    if args.json {
+        emit_json(serde_json::json!({
+            "counter_id": format_account_id(counter_contract_id, network),
+            "transactions": report.timings.len(),
+            "total_ms": report.total.as_millis(),
+            "tps": report.tps(),
+            "mean_latency_ms": report.mean(|t| t.total()).as_millis(),
+            "mean_execute_ms": report.mean(|t| t.execute).as_millis(),
+            "mean_prove_ms": report.mean(|t| t.prove).as_millis(),
+            "mean_submit_ms": report.mean(|t| t.submit).as_millis(),
+            "mean_commit_ms": report.mean(|t| t.commit).as_millis(),
+        }))?;
+        return Ok(());
+    }
+
+    println!("{}", report);
+    Ok(())
+}
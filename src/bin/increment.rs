@@ -1,111 +1,140 @@
-use std::{fs, path::Path};
-
-use masm_project_template::common::{
-    create_library, create_tx_script, delete_keystore_and_store, instantiate_client, wait_for_tx,
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
 };
-use miden_client::{
-    Word,
-    account::{Account, StorageSlotName},
-    rpc::Endpoint,
-    transaction::TransactionRequestBuilder,
+
+use clap::Parser;
+use masm_project_template::{
+    common::{
+        COUNTER_SLOT_NAME, Intent, build_intent, check_endpoint, delete_keystore_and_store,
+        emit_json, format_account_id, import_account_by_id_waiting, instantiate_client,
+        load_account, parse_connectable_network, read_storage_value, resolve_counter_id,
+        shutdown_on_ctrl_c, submit_with_prover, sync_state_retry, verify_contract_layout,
+        word_to_u64,
+    },
+    prelude::*,
 };
-use miden_protocol::account::AccountId;
+
+/// Increments a deployed counter contract
+#[derive(Parser)]
+struct Args {
+    /// Counter to increment; defaults to the one in ./counter_deployment.json for the chosen
+    /// network, then (on testnet only) the template's testnet counter
+    #[arg(long)]
+    account_id: Option<String>,
+
+    /// Network to connect to: testnet, devnet or localhost (mainnet has no endpoint yet)
+    #[arg(long, default_value = "testnet", value_parser = parse_connectable_network)]
+    network: Network,
+
+    /// Transaction script calling the counter's increment procedure
+    #[arg(long, default_value = "./masm/scripts/increment_script.masm")]
+    script_path: PathBuf,
+
+    /// Print the results as one JSON object instead
+    #[arg(long)]
+    json: bool,
+}
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::parse();
+    let json = args.json;
+    let network = args.network;
+
     delete_keystore_and_store().await;
 
     // -------------------------------------------------------------------------
     // Instantiate client
     // -------------------------------------------------------------------------
-    let endpoint = Endpoint::testnet();
-    let mut client = instantiate_client(endpoint).await.unwrap();
-
-    let sync_summary = client.sync_state().await.unwrap();
-    println!("⛓  Latest block: {}", sync_summary.block_num);
+    let endpoint = network
+        .endpoint()
+        .ok_or_else(|| format!("no RPC endpoint known for {:?}", network))?;
+    check_endpoint(&endpoint, 10_000).await?;
+    let mut client =
+        instantiate_client(endpoint, StoreBackend::default(), &RpcConfig::default()).await?;
+
+    let sync_summary = sync_state_retry(&mut client, 3).await?;
+    if !json {
+        println!("⛓  Latest block: {}", sync_summary.block_num);
+    }
 
     // -------------------------------------------------------------------------
     // STEP 1 – Query Counter State
-    let counter_contract_id = AccountId::from_bech32("mtst1azxmwd8waj5cuqq24h995zc73snfrp89")
-        .map_err(|e| format!("Invalid bech32 address: {}", e))?
-        .1;
-
-    client
-        .import_account_by_id(counter_contract_id)
-        .await
-        .unwrap();
-
-    let account_record: Account = client
-        .get_account(counter_contract_id)
-        .await?
-        .unwrap()
-        .try_into()
-        .unwrap();
-
-    let storage_slot_name = StorageSlotName::new("counter::counter_slot")?;
-    let word: Word = account_record
-        .storage()
-        .get_item(&storage_slot_name)
-        .unwrap();
-
-    let counter_val = word.get(3).unwrap().as_int();
-    println!("🔢 Counter value before tx: {}", counter_val);
+    // Without --account-id, prefer the counter deployed by the main bin, if its manifest is around
+    let counter_contract_id = resolve_counter_id(
+        args.account_id.as_deref(),
+        network,
+        Path::new("./counter_deployment.json"),
+    )?;
 
-    // -------------------------------------------------------------------------
-    // STEP 2 – Compile the increment script
-    // -------------------------------------------------------------------------
-    let script_code =
-        fs::read_to_string(Path::new("./masm/scripts/increment_script.masm")).unwrap();
+    let account_record =
+        import_account_by_id_waiting(&mut client, counter_contract_id, Duration::from_secs(30))
+            .await?;
 
-    let account_code = fs::read_to_string(Path::new("./masm/accounts/counter.masm")).unwrap();
-    let library_path = "external_contract::counter_contract";
+    verify_contract_layout(&account_record, &[COUNTER_SLOT_NAME])?;
 
-    let library = create_library(account_code, library_path).unwrap();
+    let word = read_storage_value(&account_record, COUNTER_SLOT_NAME)?;
 
-    let tx_script = create_tx_script(script_code, Some(library)).unwrap();
+    let counter_val_before = word_to_u64(&word);
+    if !json {
+        println!("🔢 Counter value before tx: {}", counter_val_before);
+    }
 
     // -------------------------------------------------------------------------
-    // STEP 3 – Build & send transaction
+    // STEP 2 – Build & send the increment transaction
     // -------------------------------------------------------------------------
-    let tx_increment_request = TransactionRequestBuilder::new()
-        .custom_script(tx_script)
-        .build()
-        .unwrap();
-
-    let tx_id = client
-        .submit_new_transaction(counter_contract_id, tx_increment_request)
-        .await
-        .unwrap();
-
-    println!("🚀 Increment transaction submitted – waiting for finality …");
-    wait_for_tx(&mut client, tx_id).await?;
+    let tx_increment_request = build_intent(
+        &mut client,
+        &account_record,
+        Intent::Increment {
+            script_path: args.script_path,
+        },
+    )
+    .await?;
+
+    // Set REMOTE_PROVER_URL to offload proving to a remote prover
+    let prover = std::env::var("REMOTE_PROVER_URL").map_or(Prover::Local, Prover::Remote);
+    if !json {
+        println!("🚀 Submitting increment transaction – waiting for finality …");
+    }
+    let tx_id = submit_with_prover(
+        &mut client,
+        counter_contract_id,
+        tx_increment_request,
+        &prover,
+        Some(shutdown_on_ctrl_c()),
+    )
+    .await?;
 
     // -------------------------------------------------------------------------
-    // STEP 4 – Fetch contract state & verify increment
+    // STEP 3 – Fetch contract state & verify increment
     // -------------------------------------------------------------------------
-    client.sync_state().await.unwrap();
-
-    let account_record: Account = client
-        .get_account(counter_contract_id)
-        .await?
-        .unwrap()
-        .try_into()
-        .unwrap();
-
-    let storage_slot_name = StorageSlotName::new("counter::counter_slot")?;
-    let word: Word = account_record
-        .storage()
-        .get_item(&storage_slot_name)
-        .unwrap();
-
-    let counter_val = word.get(3).unwrap().as_int();
-    println!("🔢 Counter value after tx: {}", counter_val);
+    sync_state_retry(&mut client, 3).await?;
+
+    let account_record = load_account(&client, counter_contract_id).await?;
+    let word = read_storage_value(&account_record, COUNTER_SLOT_NAME)?;
 
+    let counter_val = word_to_u64(&word);
+
+    if json {
+        emit_json(serde_json::json!({
+            "counter_id": format_account_id(counter_contract_id, network),
+            "counter_value_before": counter_val_before,
+            "counter_value": counter_val,
+            "tx_id": tx_id.to_hex(),
+        }))?;
+        return Ok(());
+    }
+
+    println!("🔢 Counter value after tx: {}", counter_val);
     println!("✅ Success! The counter was incremented.");
-    println!(
-        "View transaction on MidenScan: https://testnet.midenscan.com/tx/{:?}",
-        tx_id
-    );
+    if network == Network::Testnet {
+        println!(
+            "View transaction on MidenScan: https://testnet.midenscan.com/tx/{:?}",
+            tx_id
+        );
+    }
 
     Ok(())
 }
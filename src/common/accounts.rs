@@ -0,0 +1,623 @@
+// Creating, identifying & persisting accounts
+
+use miden_client::{
+    ClientError, Word,
+    account::{
+        Account, AccountBuilder, AccountComponent, AccountId, AccountStorageMode, AccountType,
+        StorageSlot,
+    },
+    asset::Asset,
+    auth::{AuthEcdsaK256Keccak, AuthSchemeId, AuthSecretKey, PublicKey, PublicKeyCommitment},
+    crypto::{
+        FeltRng, Rpo256,
+        rpo_falcon512::{PublicKey as RpoFalcon512PublicKey, SecretKey as RpoFalcon512SecretKey},
+    },
+    keystore::FilesystemKeyStore,
+    rpc::{Endpoint, NodeRpcClient, domain::account::FetchedAccount},
+    store::TransactionFilter,
+    transaction::TransactionRecord,
+};
+use miden_protocol::{
+    account::AccountComponentCode,
+    assembly::{Assembler, DefaultSourceManager, Module, ModuleKind},
+};
+use miden_standards::account::{auth::AuthFalcon512Rpo, wallets::BasicWallet};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path, sync::Arc};
+
+use super::{
+    Client, CommonError, Network,
+    client::{RpcConfig, build_rpc},
+    masm::{COUNTER_SLOT_NAME, kernel_assembler, load_library},
+    word_from_u64,
+};
+
+// Overview of an account tracked by the client's store
+#[derive(Debug, Clone)]
+pub struct AccountSummary {
+    pub id: AccountId,
+    pub account_type: AccountType,
+    pub storage_mode: AccountStorageMode,
+    pub nonce: u64,
+    pub balances: Vec<(AccountId, u64)>,
+}
+
+// Summarizes every account in the client's store
+pub async fn list_accounts(
+    client: &mut Client,
+) -> Result<Vec<AccountSummary>, Box<dyn std::error::Error>> {
+    let mut summaries = Vec::new();
+
+    for (header, _status) in client.get_account_headers().await? {
+        let id = header.id();
+        let balances = match client.get_account(id).await? {
+            Some(record) => Account::try_from(record)
+                .map_err(|e| CommonError::InvalidAccountRecord {
+                    account_id: id,
+                    reason: e.to_string(),
+                })?
+                .vault()
+                .assets()
+                .filter_map(|asset| match asset {
+                    Asset::Fungible(asset) => Some((asset.faucet_id(), asset.amount())),
+                    Asset::NonFungible(_) => None,
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+
+        summaries.push(AccountSummary {
+            id,
+            account_type: id.account_type(),
+            storage_mode: id.storage_mode(),
+            nonce: header.nonce().as_int(),
+            balances,
+        });
+    }
+
+    Ok(summaries)
+}
+
+// Fetches an account's current state from `endpoint` without adding it to the store
+pub async fn fetch_account_readonly(
+    endpoint: &Endpoint,
+    account_id: AccountId,
+) -> Result<Account, Box<dyn std::error::Error>> {
+    let rpc_client = build_rpc(endpoint, &RpcConfig::default());
+
+    match rpc_client.get_account_details(account_id).await? {
+        FetchedAccount::Public(account, _) => Ok(*account),
+        FetchedAccount::Private(..) => Err(CommonError::PrivateAccountState(account_id).into()),
+    }
+}
+
+// Lists the transactions executed against an account, oldest first
+pub async fn account_transactions(
+    client: &mut Client,
+    account_id: AccountId,
+) -> Result<Vec<TransactionRecord>, ClientError> {
+    let mut records: Vec<TransactionRecord> = client
+        .get_transactions(TransactionFilter::All)
+        .await?
+        .into_iter()
+        .filter(|record| record.details.account_id == account_id)
+        .collect();
+    records.sort_by_key(|record| record.details.creation_timestamp);
+
+    Ok(records)
+}
+
+// Renders an account id as bech32 with the prefix of `network`
+pub fn format_account_id(account_id: AccountId, network: Network) -> String {
+    account_id.to_bech32(network.network_id())
+}
+
+// Parses a bech32 account id, erroring if its network prefix isn't `expected`
+pub fn parse_account_id(
+    bech32: &str,
+    expected: Network,
+) -> Result<AccountId, Box<dyn std::error::Error>> {
+    let (network_id, account_id) = AccountId::from_bech32(bech32)?;
+
+    let expected = expected.network_id();
+    if network_id != expected {
+        return Err(CommonError::NetworkMismatch {
+            expected,
+            actual: network_id,
+        }
+        .into());
+    }
+
+    Ok(account_id)
+}
+
+// The account type encoded in the id's metadata bits; no account state is needed
+pub fn account_type_of(id: AccountId) -> AccountType {
+    id.account_type()
+}
+
+// Errors unless `id` is of the `expected` type
+pub fn expect_account_type(id: AccountId, expected: AccountType) -> Result<(), CommonError> {
+    let actual = account_type_of(id);
+    if actual != expected {
+        return Err(CommonError::UnexpectedAccountType {
+            account_id: id,
+            expected,
+            actual,
+        });
+    }
+    Ok(())
+}
+
+// A freshly created account together with its signing key
+pub struct CreatedAccount {
+    pub account: Account,
+    pub secret_key: RpoFalcon512SecretKey,
+    pub public_key: RpoFalcon512PublicKey,
+}
+
+// Like `CreatedAccount`, for accounts whose auth scheme is picked at runtime
+pub struct CreatedAuthAccount {
+    pub account: Account,
+    pub secret_key: AuthSecretKey,
+    pub public_key: PublicKey,
+}
+
+// Generates a Falcon512 keypair, returning the secret key and its public key commitment
+pub fn generate_falcon_key(rng: &mut impl FeltRng) -> (RpoFalcon512SecretKey, Word) {
+    let secret_key = RpoFalcon512SecretKey::with_rng(rng);
+    let commitment = falcon_public_commitment(&secret_key);
+    (secret_key, commitment)
+}
+
+// Public key commitment of a Falcon512 secret key, as the keystore looks it up
+pub fn falcon_public_commitment(key: &RpoFalcon512SecretKey) -> Word {
+    key.public_key().to_commitment()
+}
+
+// Fresh Falcon512 key and the `AuthFalcon512Rpo` component checking signatures against it
+fn falcon_auth_component(rng: &mut impl FeltRng) -> (RpoFalcon512SecretKey, AccountComponent) {
+    let (secret_key, commitment) = generate_falcon_key(rng);
+    let component = AccountComponent::from(AuthFalcon512Rpo::new(commitment.into()));
+    (secret_key, component)
+}
+
+// Builds a public wallet around `auth_component`, adding it to the client & its key to `keystore`
+async fn add_basic_account(
+    client: &mut Client,
+    keystore: &Arc<FilesystemKeyStore>,
+    auth_component: AccountComponent,
+    secret_key: &AuthSecretKey,
+) -> Result<Account, Box<dyn std::error::Error>> {
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+
+    let builder = AccountBuilder::new(init_seed)
+        .account_type(AccountType::RegularAccountUpdatableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(auth_component)
+        .with_component(BasicWallet);
+
+    let account = builder.build()?;
+
+    client.add_account(&account, false).await?;
+    keystore.add_key(secret_key)?;
+
+    Ok(account)
+}
+
+// Creates basic account, signing with Falcon512
+pub async fn create_basic_account(
+    client: &mut Client,
+    keystore: &Arc<FilesystemKeyStore>,
+) -> Result<CreatedAccount, Box<dyn std::error::Error>> {
+    let (secret_key, auth_component) = falcon_auth_component(client.rng());
+    let account = add_basic_account(
+        client,
+        keystore,
+        auth_component,
+        &AuthSecretKey::Falcon512Rpo(secret_key.clone()),
+    )
+    .await?;
+    let public_key = secret_key.public_key();
+
+    Ok(CreatedAccount {
+        account,
+        secret_key,
+        public_key,
+    })
+}
+
+// Creates basic account whose auth component uses `auth`, rejecting unsupported schemes
+pub async fn create_basic_account_with_auth(
+    client: &mut Client,
+    keystore: &Arc<FilesystemKeyStore>,
+    auth: AuthSchemeId,
+) -> Result<CreatedAuthAccount, Box<dyn std::error::Error>> {
+    let (secret_key, auth_component) = match auth {
+        AuthSchemeId::Falcon512Rpo => {
+            let (secret_key, component) = falcon_auth_component(client.rng());
+            (AuthSecretKey::Falcon512Rpo(secret_key), component)
+        }
+        AuthSchemeId::EcdsaK256Keccak => {
+            let secret_key = AuthSecretKey::new_ecdsa_k256_keccak();
+            let commitment = secret_key.public_key().to_commitment();
+            let component = AccountComponent::from(AuthEcdsaK256Keccak::new(commitment));
+            (secret_key, component)
+        }
+        _ => return Err(CommonError::UnsupportedAuthScheme(auth).into()),
+    };
+
+    let account = add_basic_account(client, keystore, auth_component, &secret_key).await?;
+    let public_key = secret_key.public_key();
+
+    Ok(CreatedAuthAccount {
+        account,
+        secret_key,
+        public_key,
+    })
+}
+
+// Checks whether `keystore` holds the secret key for the account's Falcon512 auth component
+pub fn keystore_has_key_for(
+    keystore: &FilesystemKeyStore,
+    account: &Account,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    let commitment = account
+        .storage()
+        .get_item(AuthFalcon512Rpo::public_key_slot())
+        .map_err(|_| CommonError::NoFalconPublicKey(account.id()))?;
+
+    Ok(keystore
+        .get_key(PublicKeyCommitment::from(commitment))?
+        .is_some())
+}
+
+pub async fn create_no_auth_component(
+    debug: bool,
+) -> Result<AccountComponent, Box<dyn std::error::Error>> {
+    let no_auth_code = fs::read_to_string(Path::new("./masm/auth/no_auth.masm"))?;
+
+    let source_manager = Arc::new(DefaultSourceManager::default());
+    let assembler: Assembler = kernel_assembler(debug, &source_manager);
+    let module = Module::parser(ModuleKind::Library).parse_str(
+        "no_auth",
+        no_auth_code,
+        source_manager.clone() as Arc<dyn miden_protocol::assembly::SourceManager>,
+    )?;
+    let library = assembler.clone().assemble_library([module])?;
+    let code = AccountComponentCode::from(library);
+
+    let no_auth_component = AccountComponent::new(code, vec![])?.with_supports_all_types();
+
+    Ok(no_auth_component)
+}
+
+// Wraps a precompiled MAST library (.masl) as an account component, skipping MASM assembly
+pub fn component_from_masl(
+    path: &Path,
+    slots: Vec<StorageSlot>,
+) -> Result<AccountComponent, Box<dyn std::error::Error>> {
+    let code = AccountComponentCode::from(load_library(path)?);
+
+    Ok(AccountComponent::new(code, slots)?.with_supports_all_types())
+}
+
+// Checks components (auth included) fit the account type before `AccountBuilder::build`
+pub fn validate_components(
+    account_type: AccountType,
+    components: &[AccountComponent],
+) -> Result<(), CommonError> {
+    let auth_components = components
+        .iter()
+        .filter(|component| {
+            component
+                .get_procedures()
+                .iter()
+                .any(|(_, is_auth)| *is_auth)
+        })
+        .count();
+    if auth_components != 1 {
+        return Err(CommonError::InvalidComponents(format!(
+            "accounts need exactly one auth component, found {}",
+            auth_components
+        )));
+    }
+
+    let wallet_root = BasicWallet::receive_asset_digest();
+    for (index, component) in components.iter().enumerate() {
+        let is_wallet = component
+            .get_procedures()
+            .iter()
+            .any(|(root, _)| *root == wallet_root);
+        if account_type.is_faucet() && is_wallet {
+            return Err(CommonError::InvalidComponents(
+                "faucet accounts cannot carry a BasicWallet".to_string(),
+            ));
+        }
+
+        if !component.supports_type(account_type) {
+            return Err(CommonError::InvalidComponents(format!(
+                "component {} doesn't support {:?} accounts (supports {:?})",
+                index,
+                account_type,
+                component.supported_types()
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+// Contract builder helper function
+pub async fn create_public_immutable_contract(
+    client: &mut Client,
+    account_code: &str,
+    debug: bool,
+) -> Result<Account, Box<dyn std::error::Error>> {
+    let slots = vec![StorageSlot::with_value(
+        COUNTER_SLOT_NAME.parse()?,
+        word_from_u64(0),
+    )];
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+
+    build_contract(
+        account_code,
+        slots,
+        init_seed,
+        AccountType::RegularAccountImmutableCode,
+        AccountStorageMode::Public,
+        debug,
+    )
+    .await
+}
+
+// Computes the id a no-auth contract built from `account_code` & `slots` gets for `seed`
+pub async fn predict_account_id(
+    account_code: &str,
+    slots: Vec<StorageSlot>,
+    seed: [u8; 32],
+    account_type: AccountType,
+    storage_mode: AccountStorageMode,
+) -> Result<AccountId, Box<dyn std::error::Error>> {
+    let account =
+        build_contract(account_code, slots, seed, account_type, storage_mode, false).await?;
+    Ok(account.id())
+}
+
+// Builds a no-auth contract whose single component is `account_code` (as module `counter`)
+async fn build_contract(
+    account_code: &str,
+    slots: Vec<StorageSlot>,
+    seed: [u8; 32],
+    account_type: AccountType,
+    storage_mode: AccountStorageMode,
+    debug: bool,
+) -> Result<Account, Box<dyn std::error::Error>> {
+    let contract_component = custom_component(account_code, slots, debug)?;
+
+    let no_auth_component = create_no_auth_component(debug).await?;
+    validate_components(
+        account_type,
+        &[no_auth_component.clone(), contract_component.clone()],
+    )?;
+
+    let contract = AccountBuilder::new(seed)
+        .account_type(account_type)
+        .storage_mode(storage_mode)
+        .with_auth_component(no_auth_component)
+        .with_component(contract_component)
+        .build()?;
+
+    Ok(contract)
+}
+
+// Assembles `account_code` (as module `counter`) into a component with `slots`
+fn custom_component(
+    account_code: &str,
+    slots: Vec<StorageSlot>,
+    debug: bool,
+) -> Result<AccountComponent, Box<dyn std::error::Error>> {
+    let source_manager = Arc::new(DefaultSourceManager::default());
+    let assembler: Assembler = kernel_assembler(debug, &source_manager);
+    let module = Module::parser(ModuleKind::Library).parse_str(
+        "counter",
+        account_code.to_owned(),
+        source_manager.clone() as Arc<dyn miden_protocol::assembly::SourceManager>,
+    )?;
+
+    let library = assembler.clone().assemble_library([module])?;
+    let code = AccountComponentCode::from(library);
+
+    Ok(AccountComponent::new(code, slots)?.with_supports_all_types())
+}
+
+// Creates a basic wallet that also carries the custom component `component_code`
+pub async fn create_wallet_with_component(
+    client: &mut Client,
+    keystore: &Arc<FilesystemKeyStore>,
+    component_code: &str,
+    slots: Vec<StorageSlot>,
+) -> Result<CreatedAccount, Box<dyn std::error::Error>> {
+    let component = custom_component(component_code, slots, true)?;
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+    let (secret_key, auth_component) = falcon_auth_component(client.rng());
+    let wallet_component = AccountComponent::from(BasicWallet);
+
+    let account_type = AccountType::RegularAccountUpdatableCode;
+    validate_components(
+        account_type,
+        &[
+            auth_component.clone(),
+            wallet_component.clone(),
+            component.clone(),
+        ],
+    )?;
+
+    let account = AccountBuilder::new(init_seed)
+        .account_type(account_type)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(auth_component)
+        .with_component(wallet_component)
+        .with_component(component)
+        .build()?;
+
+    client.add_account(&account, false).await?;
+    keystore.add_key(&AuthSecretKey::Falcon512Rpo(secret_key.clone()))?;
+    let public_key = secret_key.public_key();
+
+    Ok(CreatedAccount {
+        account,
+        secret_key,
+        public_key,
+    })
+}
+
+// Persists an account id (as hex) so later runs can resume with the same account
+pub fn save_account_id(id: AccountId, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    fs::write(path, id.to_hex())?;
+    Ok(())
+}
+
+// Loads an account id saved with `save_account_id`, if the file exists and is valid
+pub fn load_account_id(path: &Path) -> Option<AccountId> {
+    let hex = fs::read_to_string(path).ok()?;
+    AccountId::from_hex(hex.trim()).ok()
+}
+
+// What collaborators need to interact with a deployed contract
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeploymentManifest {
+    pub account_id: AccountId,
+    pub library_path: String,
+    pub network: Network,
+    // RPO hash of the contract's MASM source, to check a local copy matches what was deployed
+    pub source_hash: Word,
+}
+
+impl DeploymentManifest {
+    pub fn matches_source(&self, source: &str) -> bool {
+        self.source_hash == Rpo256::hash(source.as_bytes())
+    }
+}
+
+// On-disk JSON form of `DeploymentManifest`
+#[derive(Serialize, Deserialize)]
+struct ManifestFile {
+    account_id: String,
+    library_path: String,
+    network: Network,
+    source_hash: String,
+}
+
+// Writes a JSON manifest describing a deployed contract
+pub fn write_deployment_manifest(
+    path: &Path,
+    account_id: AccountId,
+    library_path: &str,
+    network: Network,
+    source: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let manifest = ManifestFile {
+        account_id: account_id.to_hex(),
+        library_path: library_path.to_string(),
+        network,
+        source_hash: Rpo256::hash(source.as_bytes()).to_hex(),
+    };
+    fs::write(path, serde_json::to_string_pretty(&manifest)?)?;
+    Ok(())
+}
+
+// Loads a manifest written by `write_deployment_manifest`
+pub fn load_deployment_manifest(
+    path: &Path,
+) -> Result<DeploymentManifest, Box<dyn std::error::Error>> {
+    let invalid = |reason: String| CommonError::InvalidManifest {
+        path: path.to_path_buf(),
+        reason,
+    };
+
+    let json = fs::read_to_string(path)?;
+    let manifest: ManifestFile = serde_json::from_str(&json).map_err(|e| invalid(e.to_string()))?;
+
+    Ok(DeploymentManifest {
+        account_id: AccountId::from_hex(&manifest.account_id)
+            .map_err(|e| invalid(e.to_string()))?,
+        library_path: manifest.library_path,
+        network: manifest.network,
+        source_hash: Word::try_from(manifest.source_hash.as_str())
+            .map_err(|e| invalid(e.to_string()))?,
+    })
+}
+
+// The template's counter on testnet, which the bins fall back to there
+pub const TESTNET_COUNTER_ID: &str = "mtst1azxmwd8waj5cuqq24h995zc73snfrp89";
+
+// Counter for the bins: `account_id`, else the deployment manifest's, else on testnet the default
+pub fn resolve_counter_id(
+    account_id: Option<&str>,
+    network: Network,
+    manifest_path: &Path,
+) -> Result<AccountId, Box<dyn std::error::Error>> {
+    if let Some(account_id) = account_id {
+        return parse_account_id(account_id, network);
+    }
+    if let Ok(manifest) = load_deployment_manifest(manifest_path)
+        && manifest.network == network
+    {
+        return Ok(manifest.account_id);
+    }
+    if network == Network::Testnet {
+        return parse_account_id(TESTNET_COUNTER_ID, network);
+    }
+
+    Err(CommonError::NoDefaultCounter(network).into())
+}
+
+// Imports the contract whose id is saved at `id_path`, or deploys a new one and saves its id
+pub async fn resume_or_create_contract(
+    client: &mut Client,
+    account_code: &str,
+    id_path: &Path,
+    debug: bool,
+) -> Result<Account, Box<dyn std::error::Error>> {
+    if let Some(account_id) = load_account_id(id_path) {
+        if client.get_account(account_id).await?.is_none() {
+            client.import_account_by_id(account_id).await?;
+        }
+        eprintln!("resuming account {}", account_id.to_hex());
+
+        let account: Account = client.try_get_account(account_id).await?.try_into()?;
+        return Ok(account);
+    }
+
+    let contract = create_public_immutable_contract(client, account_code, debug).await?;
+    client.add_account(&contract, false).await?;
+    save_account_id(contract.id(), id_path)?;
+    eprintln!("created account {}", contract.id().to_hex());
+
+    Ok(contract)
+}
+
+// Reads `account_id`'s full state from the client's store
+pub async fn load_account(
+    client: &Client,
+    account_id: AccountId,
+) -> Result<Account, Box<dyn std::error::Error>> {
+    let record = client
+        .get_account(account_id)
+        .await?
+        .ok_or(CommonError::AccountNotFound(account_id))?;
+
+    Ok(
+        Account::try_from(record).map_err(|e| CommonError::InvalidAccountRecord {
+            account_id,
+            reason: e.to_string(),
+        })?,
+    )
+}
@@ -0,0 +1,376 @@
+// Client, RPC & store setup, and keeping the store in sync
+
+use miden_client::{
+    ClientError,
+    account::Account,
+    block::BlockNumber,
+    builder::ClientBuilder,
+    keystore::FilesystemKeyStore,
+    rpc::{Endpoint, GrpcClient, GrpcError, NodeRpcClient, RpcError},
+    sync::SyncSummary,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use rand::Rng;
+use std::{fs, path::PathBuf, sync::Arc};
+use tokio::time::{Duration, sleep};
+
+use super::{Client, CommonError, DEFAULT_KEYSTORE_DIR, DEFAULT_STORE_PATH, error_chain_mentions};
+
+// Clears keystore & default sqlite file
+pub async fn delete_keystore_and_store() {
+    if let Err(e) = tokio::task::spawn_blocking(delete_keystore_and_store_blocking).await {
+        eprintln!("failed to clear keystore & store: {}", e);
+    }
+}
+
+// Blocking `delete_keystore_and_store`, for where awaiting isn't possible (e.g. in `Drop`)
+pub fn delete_keystore_and_store_blocking() {
+    if fs::metadata(DEFAULT_STORE_PATH).is_ok() {
+        if let Err(e) = fs::remove_file(DEFAULT_STORE_PATH) {
+            eprintln!("failed to remove {}: {}", DEFAULT_STORE_PATH, e);
+        } else {
+            eprintln!("cleared sqlite store: {}", DEFAULT_STORE_PATH);
+        }
+    } else {
+        eprintln!("store not found: {}", DEFAULT_STORE_PATH);
+    }
+
+    match fs::read_dir(DEFAULT_KEYSTORE_DIR) {
+        Ok(dir) => {
+            for entry in dir.flatten() {
+                let file_path = entry.path();
+                if let Err(e) = fs::remove_file(&file_path) {
+                    eprintln!("failed to remove {}: {}", file_path.display(), e);
+                } else {
+                    eprintln!("removed file: {}", file_path.display());
+                }
+            }
+        }
+        Err(e) => eprintln!("failed to read directory {}: {}", DEFAULT_KEYSTORE_DIR, e),
+    }
+}
+
+// Clears keystore & store unless `KEEP_STATE` is set, so a failed run can be inspected
+pub async fn maybe_cleanup() {
+    if std::env::var_os("KEEP_STATE").is_some() {
+        eprintln!("KEEP_STATE set, keeping keystore & store");
+        return;
+    }
+    delete_keystore_and_store().await;
+}
+
+// Runs `f` between keystore & store cleanups, the second also on panic; honours `KEEP_STATE`
+pub async fn with_clean_state<F, Fut, T>(f: F) -> T
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = T>,
+{
+    delete_keystore_and_store().await;
+    let _guard = CleanStateGuard;
+    f().await
+}
+
+struct CleanStateGuard;
+
+impl Drop for CleanStateGuard {
+    // Blocking removal: drop can't await, and may run while unwinding a panic
+    fn drop(&mut self) {
+        if std::env::var_os("KEEP_STATE").is_some() {
+            eprintln!("KEEP_STATE set, keeping keystore & store");
+            return;
+        }
+        delete_keystore_and_store_blocking();
+    }
+}
+
+// Fetches the latest block header to confirm the node is reachable, returning the chain tip
+pub async fn check_endpoint(
+    endpoint: &Endpoint,
+    timeout_ms: u64,
+) -> Result<BlockNumber, CommonError> {
+    let rpc_client = GrpcClient::new(endpoint, timeout_ms);
+
+    match rpc_client.get_block_header_by_number(None, false).await {
+        Ok((header, _)) => Ok(header.block_num()),
+        Err(e) => Err(CommonError::EndpointUnreachable {
+            endpoint: endpoint.to_string(),
+            reason: e.to_string(),
+        }),
+    }
+}
+
+// RPC settings; the connect timeout & keepalive are applied by `instantiate_client_with_keystore`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RpcConfig {
+    pub timeout_ms: u64,
+    pub connect_timeout_ms: Option<u64>,
+    pub keepalive: Option<Duration>,
+}
+
+impl Default for RpcConfig {
+    fn default() -> Self {
+        Self {
+            timeout_ms: 10_000,
+            connect_timeout_ms: None,
+            keepalive: None,
+        }
+    }
+}
+
+// What `instantiate_client` was called with, for helpers that need a second client
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    pub endpoint: Endpoint,
+    pub store: StoreBackend,
+    pub rpc: RpcConfig,
+}
+
+pub fn build_rpc(endpoint: &Endpoint, config: &RpcConfig) -> Arc<GrpcClient> {
+    Arc::new(GrpcClient::new(endpoint, config.timeout_ms))
+}
+
+// Opens `rpc_client`'s channel within `connect_timeout_ms`, so an unreachable node fails fast
+async fn connect_rpc(
+    rpc_client: &GrpcClient,
+    endpoint: &Endpoint,
+    connect_timeout_ms: u64,
+) -> Result<(), CommonError> {
+    let unreachable = |reason: String| CommonError::EndpointUnreachable {
+        endpoint: endpoint.to_string(),
+        reason,
+    };
+    let connect = rpc_client.get_block_header_by_number(None, false);
+    match tokio::time::timeout(Duration::from_millis(connect_timeout_ms), connect).await {
+        Ok(Ok(_)) => Ok(()),
+        Ok(Err(e)) => Err(unreachable(e.to_string())),
+        Err(_) => Err(unreachable(format!(
+            "no connection within {}ms",
+            connect_timeout_ms
+        ))),
+    }
+}
+
+// Pings the node every `interval` until the client owning `rpc_client` is dropped
+fn spawn_keepalive(rpc_client: &Arc<GrpcClient>, interval: Duration) {
+    let rpc_client = Arc::downgrade(rpc_client);
+    tokio::spawn(async move {
+        loop {
+            sleep(interval).await;
+            let Some(rpc_client) = rpc_client.upgrade() else {
+                break;
+            };
+            // A failed ping is left for the client's next request to report
+            let _ = rpc_client.get_block_header_by_number(None, false).await;
+        }
+    });
+}
+
+// Where the client keeps its state
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StoreBackend {
+    Sqlite(PathBuf),
+    // Throwaway store for tests; build it with `StoreBackend::in_memory()`
+    InMemory(PathBuf),
+}
+
+impl Default for StoreBackend {
+    fn default() -> Self {
+        StoreBackend::Sqlite(PathBuf::from(DEFAULT_STORE_PATH))
+    }
+}
+
+impl StoreBackend {
+    // Fresh, uniquely named shared-cache sqlite memory database, shared by clones of the value
+    pub fn in_memory() -> Self {
+        StoreBackend::InMemory(PathBuf::from(format!(
+            "file:masm_store_{:016x}?mode=memory&cache=shared",
+            rand::rng().random::<u64>()
+        )))
+    }
+
+    // Path handed to the sqlite store
+    pub fn sqlite_path(&self) -> PathBuf {
+        match self {
+            StoreBackend::Sqlite(path) | StoreBackend::InMemory(path) => path.clone(),
+        }
+    }
+}
+
+// Helper to instantiate Client; `rpc_config` sets the node RPC timeout
+pub async fn instantiate_client(
+    endpoint: Endpoint,
+    store: StoreBackend,
+    rpc_config: &RpcConfig,
+) -> Result<Client, Box<dyn std::error::Error>> {
+    let keystore = Arc::new(FilesystemKeyStore::new(PathBuf::from(
+        DEFAULT_KEYSTORE_DIR,
+    ))?);
+
+    instantiate_client_with_keystore(endpoint, store.sqlite_path(), keystore, rpc_config).await
+}
+
+// Instantiates a Client over its own store & keystore, e.g. one per user in a multi-party demo
+pub async fn instantiate_client_with_keystore(
+    endpoint: Endpoint,
+    store_path: PathBuf,
+    keystore: Arc<FilesystemKeyStore>,
+    rpc_config: &RpcConfig,
+) -> Result<Client, Box<dyn std::error::Error>> {
+    let rpc_client = build_rpc(&endpoint, rpc_config);
+    if let Some(connect_timeout_ms) = rpc_config.connect_timeout_ms {
+        connect_rpc(&rpc_client, &endpoint, connect_timeout_ms).await?;
+    }
+
+    // Another process holding the store surfaces as "database is locked", so retry a few times
+    let max_attempts = 3;
+    let mut attempt = 1;
+    loop {
+        let result = ClientBuilder::new()
+            .rpc(rpc_client.clone())
+            .sqlite_store(store_path.clone())
+            .authenticator(keystore.clone())
+            .in_debug_mode(true.into())
+            .build()
+            .await;
+
+        match result {
+            Ok(client) => {
+                if let Some(interval) = rpc_config.keepalive {
+                    spawn_keepalive(&rpc_client, interval);
+                }
+                return Ok(client);
+            }
+            Err(e) if is_store_locked(&e) => {
+                if attempt == max_attempts {
+                    return Err(CommonError::StoreLocked(store_path).into());
+                }
+                eprintln!(
+                    "store {} is locked, retrying ({}/{})",
+                    store_path.display(),
+                    attempt,
+                    max_attempts
+                );
+                attempt += 1;
+                sleep(Duration::from_millis(500)).await;
+            }
+            Err(e) if is_store_schema_outdated(&e) => {
+                return Err(CommonError::StoreSchemaOutdated {
+                    path: store_path,
+                    reason: e.to_string(),
+                }
+                .into());
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+// Checks the error chain for sqlite's "database is locked" condition
+fn is_store_locked(err: &(dyn std::error::Error + 'static)) -> bool {
+    error_chain_mentions(err, &["database is locked", "database is busy"])
+}
+
+// Whether the store failed its schema migration, e.g. one left by another client version
+fn is_store_schema_outdated(err: &(dyn std::error::Error + 'static)) -> bool {
+    error_chain_mentions(err, &["migration"])
+}
+
+// Whether an error is a momentary RPC failure (timeout, node unavailable) worth retrying
+pub fn is_transient_rpc_error(err: &ClientError) -> bool {
+    match err {
+        ClientError::RpcError(RpcError::ConnectionError(_)) => true,
+        ClientError::RpcError(RpcError::GrpcError { error_kind, .. }) => matches!(
+            error_kind,
+            GrpcError::Unavailable
+                | GrpcError::DeadlineExceeded
+                | GrpcError::ResourceExhausted
+                | GrpcError::Aborted
+        ),
+        _ => false,
+    }
+}
+
+// Syncs the client, retrying transient RPC errors with exponential backoff
+pub async fn sync_state_retry(
+    client: &mut Client,
+    max_retries: usize,
+) -> Result<SyncSummary, ClientError> {
+    let mut attempt = 0;
+    let mut delay = Duration::from_millis(500);
+
+    loop {
+        match client.sync_state().await {
+            Ok(summary) => return Ok(summary),
+            Err(e) if attempt < max_retries && is_transient_rpc_error(&e) => {
+                attempt += 1;
+                eprintln!(
+                    "sync failed ({}), retrying in {:?} ({}/{})",
+                    e, delay, attempt, max_retries
+                );
+                sleep(delay).await;
+                delay *= 2;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+// Syncs and returns the chain tip
+pub async fn chain_head(client: &mut Client) -> Result<BlockNumber, ClientError> {
+    Ok(sync_state_retry(client, 3).await?.block_num)
+}
+
+// Wipes & resyncs the store `client` was instantiated with, keeping its tracked accounts
+pub async fn force_full_resync(
+    client: Client,
+    endpoint: Endpoint,
+    store: &StoreBackend,
+    keystore: Arc<FilesystemKeyStore>,
+    rpc_config: &RpcConfig,
+) -> Result<(Client, SyncSummary), Box<dyn std::error::Error>> {
+    let mut accounts = Vec::new();
+    for (header, _status) in client.get_account_headers().await? {
+        let account: Account = client.try_get_account(header.id()).await?.try_into()?;
+        accounts.push(account);
+    }
+    drop(client);
+
+    if let StoreBackend::Sqlite(store_path) = store
+        && tokio::fs::metadata(store_path).await.is_ok()
+    {
+        tokio::fs::remove_file(store_path).await?;
+        eprintln!("cleared sqlite store: {}", store_path.display());
+    }
+
+    let mut client =
+        instantiate_client_with_keystore(endpoint, store.sqlite_path(), keystore, rpc_config)
+            .await?;
+    for account in &accounts {
+        if !account.is_public() {
+            client.add_account(account, false).await?;
+        } else if let Err(e) = client.import_account_by_id(account.id()).await {
+            if !is_account_not_found(&e) {
+                return Err(e.into());
+            }
+            client.add_account(account, false).await?;
+        }
+        eprintln!("restored account {}", account.id().to_hex());
+    }
+
+    let summary = sync_state_retry(&mut client, 3).await?;
+    eprintln!("resynced to block {}", summary.block_num);
+
+    Ok((client, summary))
+}
+
+// Whether the node reported an account as unknown, as opposed to failing to answer
+fn is_account_not_found(err: &ClientError) -> bool {
+    matches!(
+        err,
+        ClientError::AccountDataNotFound(_)
+            | ClientError::RpcError(RpcError::GrpcError {
+                error_kind: GrpcError::NotFound,
+                ..
+            })
+    )
+}
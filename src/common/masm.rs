@@ -0,0 +1,268 @@
+// Assembling MASM libraries & scripts, and checking MASM sources
+
+use miden_client::{
+    Deserializable, Serializable, Word,
+    account::StorageSlotName,
+    assembly::CodeBuilder,
+    note::NoteScript,
+    transaction::{TransactionKernel, TransactionScript},
+};
+use miden_protocol::assembly::{
+    Assembler, DefaultSourceManager, Library, Module, ModuleKind, diagnostics::Report,
+};
+use miden_standards::StandardsLib;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use super::CommonError;
+
+// Builds the kernel assembler, sharing the MASM's source manager in debug mode
+pub(crate) fn kernel_assembler(
+    debug: bool,
+    source_manager: &Arc<DefaultSourceManager>,
+) -> Assembler {
+    if debug {
+        TransactionKernel::assembler_with_source_manager(source_manager.clone())
+    } else {
+        TransactionKernel::assembler()
+    }
+}
+
+// Creates library
+pub fn create_library(
+    account_code: String,
+    library_path: &str,
+    debug: bool,
+) -> Result<Library, Box<dyn std::error::Error>> {
+    let source_manager = Arc::new(DefaultSourceManager::default());
+    let assembler: Assembler = kernel_assembler(debug, &source_manager);
+    let module = Module::parser(ModuleKind::Library).parse_str(
+        library_path,
+        account_code,
+        source_manager.clone() as Arc<dyn miden_protocol::assembly::SourceManager>,
+    )?;
+    let library = assembler.clone().assemble_library([module])?;
+    Ok(library)
+}
+
+// Writes a compiled library to disk (.masl), so it can be loaded instead of re-assembled
+pub fn save_library(library: &Library, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    fs::write(path, library.to_bytes())?;
+    Ok(())
+}
+
+// Reads a library written by `save_library`, rejecting bytes that don't form a valid library
+pub fn load_library(path: &Path) -> Result<Library, Box<dyn std::error::Error>> {
+    let bytes = fs::read(path)?;
+    let library =
+        Library::read_from_bytes(&bytes).map_err(|e| CommonError::InvalidLibraryFile {
+            path: path.to_path_buf(),
+            reason: e.to_string(),
+        })?;
+    Ok(library)
+}
+
+// Lists the procedures a library exports, as (path usable in a script `use`/`call`, MAST root)
+pub fn procedure_roots(library: &Library) -> Vec<(String, Word)> {
+    library
+        .exports()
+        .filter_map(|export| export.as_procedure())
+        .filter_map(|export| {
+            let root = library.get_procedure_root_by_path(&export.path)?;
+            let path = export.path.to_string();
+            Some((path.trim_start_matches("::").to_string(), root))
+        })
+        .collect()
+}
+
+// Heuristic text scan of MASM source for `word("...")` constants naming storage slots
+pub fn storage_slots_in_source(
+    account_code: &str,
+) -> Result<Vec<StorageSlotName>, Box<dyn std::error::Error>> {
+    let mut slots = Vec::new();
+    for line in account_code.lines() {
+        let code = line.split('#').next().unwrap_or_default();
+        let mut rest = code;
+        while let Some(start) = rest.find("word(\"") {
+            let after = &rest[start + "word(\"".len()..];
+            let Some(end) = after.find("\")") else {
+                break;
+            };
+            let slot = StorageSlotName::new(&after[..end])?;
+            if !slots.contains(&slot) {
+                slots.push(slot);
+            }
+            rest = &after[end..];
+        }
+    }
+    Ok(slots)
+}
+
+// Checks that account/component MASM assembles, without building an account
+pub fn check_masm(code: &str, library_path: &str) -> Result<(), Report> {
+    let source_manager = Arc::new(DefaultSourceManager::default());
+    let assembler: Assembler = kernel_assembler(true, &source_manager);
+    let module = Module::parser(ModuleKind::Library).parse_str(
+        library_path,
+        code,
+        source_manager.clone() as Arc<dyn miden_protocol::assembly::SourceManager>,
+    )?;
+    assembler.assemble_library([module])?;
+    Ok(())
+}
+
+// Reads a MASM file and checks it assembles, using the file stem as library path
+pub fn check_masm_file(path: &Path) -> Result<(), Report> {
+    let code = fs::read_to_string(path)
+        .map_err(|e| Report::msg(format!("failed to read {}: {}", path.display(), e)))?;
+    let library_path = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .ok_or_else(|| Report::msg(format!("invalid file name: {}", path.display())))?;
+
+    check_masm(&code, library_path)
+}
+
+// Assembles every `.masm` file under `root` as a program or library, collecting all failures
+pub fn check_masm_dir(root: &Path, libraries: &[Library]) -> Result<(), Vec<(PathBuf, Report)>> {
+    let mut files = Vec::new();
+    let mut failures = Vec::new();
+    collect_masm_files(root, &mut files, &mut failures);
+    files.sort();
+
+    for path in files {
+        let result = fs::read_to_string(&path)
+            .map_err(|e| Report::msg(format!("failed to read {}: {}", path.display(), e)))
+            .and_then(|code| {
+                let is_program = code.lines().any(|line| {
+                    let line = line.trim();
+                    line == "begin" || line.starts_with("begin ")
+                });
+                if is_program {
+                    check_masm_program(code, libraries)
+                } else {
+                    check_masm_file(&path)
+                }
+            });
+        if let Err(e) = result {
+            failures.push((path, e));
+        }
+    }
+
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(failures)
+    }
+}
+
+fn collect_masm_files(dir: &Path, files: &mut Vec<PathBuf>, failures: &mut Vec<(PathBuf, Report)>) {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            let reason = format!("failed to read directory {}: {}", dir.display(), e);
+            failures.push((dir.to_path_buf(), Report::msg(reason)));
+            return;
+        }
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_masm_files(&path, files, failures);
+        } else if path.extension().is_some_and(|ext| ext == "masm") {
+            files.push(path);
+        }
+    }
+}
+
+fn check_masm_program(code: String, libraries: &[Library]) -> Result<(), Report> {
+    let source_manager = Arc::new(DefaultSourceManager::default());
+    let mut assembler = kernel_assembler(true, &source_manager);
+    assembler.link_dynamic_library(StandardsLib::default())?;
+    for library in libraries {
+        assembler.link_dynamic_library(library)?;
+    }
+    assembler.assemble_program(code)?;
+    Ok(())
+}
+
+pub fn create_tx_script(
+    script_code: String,
+    library: Option<Library>,
+) -> Result<TransactionScript, Box<dyn std::error::Error>> {
+    if let Some(lib) = library {
+        return Ok(CodeBuilder::new()
+            .with_dynamically_linked_library(&lib)?
+            .compile_tx_script(script_code)?);
+    };
+
+    Ok(CodeBuilder::new().compile_tx_script(script_code)?)
+}
+
+// Path the counter library is assembled under; must match the script & note `use` lines
+pub const COUNTER_LIBRARY_PATH: &str = "external_contract::counter_contract";
+
+// Value slot the counter contract keeps its count in, as named in `masm/accounts/counter.masm`
+pub const COUNTER_SLOT_NAME: &str = "counter::counter_slot";
+
+pub fn counter_library_path() -> &'static str {
+    COUNTER_LIBRARY_PATH
+}
+
+// Counter library plus the note & tx scripts that call into it
+pub struct CounterArtifacts {
+    pub library: Library,
+    pub note_script: NoteScript,
+    pub tx_script: TransactionScript,
+}
+
+// Compiles the counter library once and links both the note & tx script against it
+pub fn build_counter_artifacts(
+    account_code: &str,
+    note_code: &str,
+    script_code: &str,
+) -> Result<CounterArtifacts, Box<dyn std::error::Error>> {
+    let library = create_library(account_code.to_string(), COUNTER_LIBRARY_PATH, true)?;
+
+    let note_script = CodeBuilder::new()
+        .with_dynamically_linked_library(&library)?
+        .compile_note_script(note_code)?;
+    let tx_script = CodeBuilder::new()
+        .with_dynamically_linked_library(&library)?
+        .compile_tx_script(script_code)?;
+
+    Ok(CounterArtifacts {
+        library,
+        note_script,
+        tx_script,
+    })
+}
+
+// Compiles a tx script after replacing `{{placeholder}}` tokens with the given values
+pub fn compile_tx_script_templated(
+    template: &str,
+    substitutions: &[(&str, String)],
+    libraries: &[Library],
+) -> Result<TransactionScript, Box<dyn std::error::Error>> {
+    let mut script_code = template.to_string();
+    for (placeholder, value) in substitutions {
+        script_code = script_code.replace(&format!("{{{{{}}}}}", placeholder), value);
+    }
+
+    if let Some(start) = script_code.find("{{") {
+        let rest = &script_code[start + 2..];
+        let name = rest.split("}}").next().unwrap_or(rest).trim();
+        return Err(CommonError::UnfilledPlaceholder(name.to_string()).into());
+    }
+
+    let mut builder = CodeBuilder::new();
+    for library in libraries {
+        builder = builder.with_dynamically_linked_library(library)?;
+    }
+
+    Ok(builder.compile_tx_script(script_code)?)
+}
@@ -0,0 +1,603 @@
+// Helpers shared by the bins & tests, split by what they act on and re-exported here
+
+use miden_client::{
+    Client as MidenClient, Felt, Word,
+    account::{AccountId, AccountType},
+    asset::NonFungibleAsset,
+    auth::AuthSchemeId,
+    block::BlockNumber,
+    keystore::FilesystemKeyStore,
+    note::{NoteId, NoteType},
+    rpc::Endpoint,
+    transaction::TransactionId,
+};
+use miden_protocol::address::NetworkId;
+use serde::{Deserialize, Serialize};
+use std::{fmt, path::PathBuf, str::FromStr};
+use tokio::time::Duration;
+
+mod accounts;
+mod client;
+mod masm;
+mod notes;
+mod storage;
+mod transactions;
+mod wait;
+
+pub use accounts::*;
+pub use client::*;
+pub use masm::*;
+pub use notes::*;
+pub use storage::*;
+pub use transactions::*;
+pub use wait::*;
+
+// The client every helper here takes, keyed by the filesystem keystore
+pub type Client = MidenClient<FilesystemKeyStore>;
+
+// Errors surfaced by the helpers in this module
+#[derive(Debug)]
+pub enum CommonError {
+    NoteScriptMismatch {
+        note_id: NoteId,
+        expected: Word,
+        actual: Word,
+    },
+    StoreLocked(PathBuf),
+    InvalidNoteFile {
+        path: PathBuf,
+        reason: String,
+    },
+    StorageWaitTimeout {
+        account_id: AccountId,
+        slot_name: String,
+        expected: u64,
+        last_observed: Option<u64>,
+    },
+    StorageSlotNotFound(String),
+    NotAMapSlot(String),
+    InsufficientBalance {
+        faucet_id: AccountId,
+        requested: u64,
+        available: u64,
+    },
+    MissingNonFungibleAsset(NonFungibleAsset),
+    NoteNotFound(NoteId),
+    NetworkMismatch {
+        expected: NetworkId,
+        actual: NetworkId,
+    },
+    UnfilledPlaceholder(String),
+    EndpointUnreachable {
+        endpoint: String,
+        reason: String,
+    },
+    InvalidLibraryFile {
+        path: PathBuf,
+        reason: String,
+    },
+    TransactionExpired {
+        tx_id: TransactionId,
+        expiration_block: BlockNumber,
+    },
+    TransactionDiscarded {
+        tx_id: TransactionId,
+        cause: String,
+    },
+    AccountCommitTimeout {
+        account_id: AccountId,
+        waited: Duration,
+    },
+    NotAValueSlot(String),
+    MissingProcedure {
+        account_id: AccountId,
+        procedure: String,
+    },
+    NonceWaitTimeout {
+        account_id: AccountId,
+        min_nonce: u64,
+        last_observed: Option<u64>,
+    },
+    InvalidComponents(String),
+    NoFalconPublicKey(AccountId),
+    TooManyAssets {
+        count: usize,
+        max: usize,
+    },
+    AccountsDisagree {
+        account_id: AccountId,
+        diff: AccountDiff,
+    },
+    PartialSubmission {
+        submitted: Vec<TransactionId>,
+        failed: Vec<(AccountId, String)>,
+    },
+    UnsupportedNoteType(NoteType),
+    PrivateAccountState(AccountId),
+    InvalidManifest {
+        path: PathBuf,
+        reason: String,
+    },
+    ExceedsMaxSupply {
+        faucet_id: AccountId,
+        requested: u64,
+        issued: u64,
+        max_supply: u64,
+    },
+    NotAP2idNote(NoteId),
+    AccountImportTimeout {
+        account_id: AccountId,
+        waited: Duration,
+    },
+    NoteWaitTimeout {
+        note_id: NoteId,
+        waited: Duration,
+    },
+    UnsupportedAuthScheme(AuthSchemeId),
+    StoreSchemaOutdated {
+        path: PathBuf,
+        reason: String,
+    },
+    InvalidMerkleProof(String),
+    UnexpectedStorageLayout {
+        account_id: AccountId,
+        missing: Vec<String>,
+        found: Vec<String>,
+    },
+    PartialImport {
+        imported: Vec<AccountId>,
+        failed: Vec<(AccountId, String)>,
+    },
+    AlreadyConsumed(NoteId),
+    UnexpectedAccountType {
+        account_id: AccountId,
+        expected: AccountType,
+        actual: AccountType,
+    },
+    BlockWaitTimeout {
+        target: BlockNumber,
+        reached: BlockNumber,
+        waited: Duration,
+    },
+    NotConsumable {
+        account_id: AccountId,
+        note_ids: Vec<NoteId>,
+    },
+    InvalidSnapshot {
+        path: PathBuf,
+        reason: String,
+    },
+    StateMismatch {
+        path: PathBuf,
+        diff: AccountDiff,
+    },
+    UnexpectedOutputNotes {
+        expected: usize,
+        created: usize,
+    },
+    ForeignStore {
+        tx_id: TransactionId,
+        path: PathBuf,
+    },
+    NoDefaultCounter(Network),
+    InvalidAccountRecord {
+        account_id: AccountId,
+        reason: String,
+    },
+    AccountNotFound(AccountId),
+}
+
+impl fmt::Display for CommonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommonError::NoteScriptMismatch {
+                note_id,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "note {} has script root {} but {} was expected",
+                note_id.to_hex(),
+                actual.to_hex(),
+                expected.to_hex()
+            ),
+            CommonError::StoreLocked(path) => write!(
+                f,
+                "sqlite store {} is locked by another process; \
+                 use a distinct store path per running client",
+                path.display()
+            ),
+            CommonError::InvalidNoteFile { path, reason } => write!(
+                f,
+                "{} does not contain a valid note: {}",
+                path.display(),
+                reason
+            ),
+            CommonError::StorageWaitTimeout {
+                account_id,
+                slot_name,
+                expected,
+                last_observed,
+            } => write!(
+                f,
+                "timed out waiting for {} on account {} to equal {} (last observed: {})",
+                slot_name,
+                account_id.to_hex(),
+                expected,
+                last_observed.map_or("none".to_string(), |v| v.to_string())
+            ),
+            CommonError::StorageSlotNotFound(slot_name) => {
+                write!(f, "storage slot {} not found", slot_name)
+            }
+            CommonError::NotAMapSlot(slot_name) => {
+                write!(f, "storage slot {} is a value slot, not a map", slot_name)
+            }
+            CommonError::InsufficientBalance {
+                faucet_id,
+                requested,
+                available,
+            } => write!(
+                f,
+                "insufficient balance of faucet {}: requested {}, available {}",
+                faucet_id.to_hex(),
+                requested,
+                available
+            ),
+            CommonError::MissingNonFungibleAsset(asset) => {
+                write!(f, "account does not hold non-fungible asset {:?}", asset)
+            }
+            CommonError::NoteNotFound(note_id) => {
+                write!(f, "note {} not found in the store", note_id.to_hex())
+            }
+            CommonError::NetworkMismatch { expected, actual } => write!(
+                f,
+                "address belongs to network {} but {} was expected",
+                actual, expected
+            ),
+            CommonError::UnfilledPlaceholder(name) => {
+                write!(
+                    f,
+                    "template placeholder {{{{{}}}}} was not substituted",
+                    name
+                )
+            }
+            CommonError::EndpointUnreachable { endpoint, reason } => {
+                write!(f, "cannot reach node at {}: {}", endpoint, reason)
+            }
+            CommonError::InvalidLibraryFile { path, reason } => write!(
+                f,
+                "{} is not a valid compiled library: {}",
+                path.display(),
+                reason
+            ),
+            CommonError::TransactionExpired {
+                tx_id,
+                expiration_block,
+            } => write!(
+                f,
+                "transaction {} expired at block {} without being committed",
+                tx_id.to_hex(),
+                expiration_block
+            ),
+            CommonError::TransactionDiscarded { tx_id, cause } => {
+                write!(f, "transaction {} was discarded: {}", tx_id.to_hex(), cause)
+            }
+            CommonError::AccountCommitTimeout { account_id, waited } => write!(
+                f,
+                "account {} was not committed on-chain within {}s",
+                account_id.to_hex(),
+                waited.as_secs()
+            ),
+            CommonError::NotAValueSlot(slot_name) => {
+                write!(f, "storage slot {} is not a value slot", slot_name)
+            }
+            CommonError::MissingProcedure {
+                account_id,
+                procedure,
+            } => write!(
+                f,
+                "account {} does not export {}",
+                account_id.to_hex(),
+                procedure
+            ),
+            CommonError::NonceWaitTimeout {
+                account_id,
+                min_nonce,
+                last_observed,
+            } => write!(
+                f,
+                "timed out waiting for nonce of account {} to reach {} (last observed: {})",
+                account_id.to_hex(),
+                min_nonce,
+                last_observed.map_or("none".to_string(), |v| v.to_string())
+            ),
+            CommonError::InvalidComponents(reason) => {
+                write!(f, "invalid account components: {}", reason)
+            }
+            CommonError::NoFalconPublicKey(account_id) => {
+                write!(f, "account {} has no Falcon512 public key slot", account_id)
+            }
+            CommonError::TooManyAssets { count, max } => {
+                write!(f, "note has {} assets, at most {} are allowed", count, max)
+            }
+            CommonError::AccountsDisagree { account_id, diff } => write!(
+                f,
+                "clients disagree on account {}, {}",
+                account_id.to_hex(),
+                diff
+            ),
+            CommonError::PartialSubmission { submitted, failed } => {
+                write!(
+                    f,
+                    "{} transactions submitted, {} accounts failed:",
+                    submitted.len(),
+                    failed.len()
+                )?;
+                for (account_id, reason) in failed {
+                    write!(f, " {} ({});", account_id.to_hex(), reason)?;
+                }
+                Ok(())
+            }
+            CommonError::UnsupportedNoteType(note_type) => {
+                write!(f, "{} notes are not supported yet", note_type)
+            }
+            CommonError::PrivateAccountState(account_id) => write!(
+                f,
+                "account {} is private, its state isn't available from the node",
+                account_id.to_hex()
+            ),
+            CommonError::InvalidManifest { path, reason } => write!(
+                f,
+                "{} is not a valid deployment manifest: {}",
+                path.display(),
+                reason
+            ),
+            CommonError::ExceedsMaxSupply {
+                faucet_id,
+                requested,
+                issued,
+                max_supply,
+            } => write!(
+                f,
+                "minting {} from faucet {} would exceed max supply: {} of {} already issued",
+                requested,
+                faucet_id.to_hex(),
+                issued,
+                max_supply
+            ),
+            CommonError::NotAP2idNote(note_id) => {
+                write!(f, "note {} is not a P2ID note", note_id.to_hex())
+            }
+            CommonError::AccountImportTimeout { account_id, waited } => write!(
+                f,
+                "account {} could not be imported from the node within {}s",
+                account_id.to_hex(),
+                waited.as_secs()
+            ),
+            CommonError::NoteWaitTimeout { note_id, waited } => write!(
+                f,
+                "note {} did not appear within {}s",
+                note_id.to_hex(),
+                waited.as_secs()
+            ),
+            CommonError::UnsupportedAuthScheme(scheme) => {
+                write!(
+                    f,
+                    "auth scheme {:?} is not supported for basic accounts",
+                    scheme
+                )
+            }
+            CommonError::StoreSchemaOutdated { path, reason } => write!(
+                f,
+                "sqlite store {} was written by an incompatible miden-client version ({}); \
+                 delete it (or migrate it) and sync again",
+                path.display(),
+                reason
+            ),
+            CommonError::InvalidMerkleProof(reason) => {
+                write!(f, "invalid merkle proof: {}", reason)
+            }
+            CommonError::UnexpectedStorageLayout {
+                account_id,
+                missing,
+                found,
+            } => write!(
+                f,
+                "account {} does not declare storage slot(s) {}; it declares [{}]",
+                account_id.to_hex(),
+                missing.join(", "),
+                found.join(", ")
+            ),
+            CommonError::PartialImport { imported, failed } => {
+                write!(
+                    f,
+                    "{} accounts imported, {} failed:",
+                    imported.len(),
+                    failed.len()
+                )?;
+                for (account_id, reason) in failed {
+                    write!(f, " {} ({});", account_id.to_hex(), reason)?;
+                }
+                Ok(())
+            }
+            CommonError::AlreadyConsumed(note_id) => {
+                write!(f, "note {} was already consumed", note_id.to_hex())
+            }
+            CommonError::UnexpectedAccountType {
+                account_id,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "account {} is a {:?} account, expected a {:?} one",
+                account_id.to_hex(),
+                actual,
+                expected
+            ),
+            CommonError::BlockWaitTimeout {
+                target,
+                reached,
+                waited,
+            } => write!(
+                f,
+                "chain reached block {} but not {} within {}s; the node can't be made to produce \
+                 blocks on demand, so it must be producing them on its own",
+                reached,
+                target,
+                waited.as_secs()
+            ),
+            CommonError::NotConsumable {
+                account_id,
+                note_ids,
+            } => {
+                let note_ids: Vec<String> = note_ids.iter().map(|id| id.to_hex()).collect();
+                write!(
+                    f,
+                    "account {} cannot consume note(s) {}",
+                    account_id.to_hex(),
+                    note_ids.join(", ")
+                )
+            }
+            CommonError::InvalidSnapshot { path, reason } => {
+                write!(f, "invalid account snapshot {}: {}", path.display(), reason)
+            }
+            CommonError::StateMismatch { path, diff } => write!(
+                f,
+                "account state differs from the snapshot in {}, {}",
+                path.display(),
+                diff
+            ),
+            CommonError::UnexpectedOutputNotes { expected, created } => write!(
+                f,
+                "transaction created {} notes but the request expected {}; nothing was submitted",
+                created, expected
+            ),
+            CommonError::ForeignStore { tx_id, path } => write!(
+                f,
+                "transaction {} was executed against store {}, which isn't the submitting client's",
+                tx_id.to_hex(),
+                path.display()
+            ),
+            CommonError::NoDefaultCounter(network) => write!(
+                f,
+                "no counter deployed on {:?} is known; pass --account-id",
+                network
+            ),
+            CommonError::InvalidAccountRecord { account_id, reason } => write!(
+                f,
+                "stored record of account {} can't be read back: {}",
+                account_id.to_hex(),
+                reason
+            ),
+            CommonError::AccountNotFound(account_id) => write!(
+                f,
+                "account {} isn't in the client's store; import it first",
+                account_id.to_hex()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CommonError {}
+
+// Networks the template can be pointed at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Network {
+    Mainnet,
+    Testnet,
+    Devnet,
+    Localhost,
+}
+
+impl Network {
+    // Network id whose prefix is used for bech32 addresses on this network
+    pub fn network_id(&self) -> NetworkId {
+        match self {
+            Network::Mainnet => NetworkId::Mainnet,
+            Network::Testnet => NetworkId::Testnet,
+            Network::Devnet => NetworkId::Devnet,
+            Network::Localhost => Endpoint::localhost().to_network_id(),
+        }
+    }
+
+    // RPC endpoint of the network's node; the client ships no mainnet endpoint yet
+    pub fn endpoint(&self) -> Option<Endpoint> {
+        match self {
+            Network::Mainnet => None,
+            Network::Testnet => Some(Endpoint::testnet()),
+            Network::Devnet => Some(Endpoint::devnet()),
+            Network::Localhost => Some(Endpoint::localhost()),
+        }
+    }
+}
+
+// clap `value_parser` for bins that connect to the network, rejecting ones without an endpoint
+pub fn parse_connectable_network(s: &str) -> Result<Network, String> {
+    let network: Network = s.parse()?;
+    match network.endpoint() {
+        Some(_) => Ok(network),
+        None => Err(format!(
+            "no RPC endpoint known for {:?} yet, expected testnet, devnet or localhost",
+            network
+        )),
+    }
+}
+
+impl FromStr for Network {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "mainnet" => Ok(Network::Mainnet),
+            "testnet" => Ok(Network::Testnet),
+            "devnet" => Ok(Network::Devnet),
+            "localhost" => Ok(Network::Localhost),
+            other => Err(format!(
+                "unknown network {:?}, expected mainnet, testnet, devnet or localhost",
+                other
+            )),
+        }
+    }
+}
+
+// Where `StoreBackend::default()` and `instantiate_client` keep the store & keys
+pub const DEFAULT_STORE_PATH: &str = "./store.sqlite3";
+pub const DEFAULT_KEYSTORE_DIR: &str = "./keystore";
+
+// Whether any error in the chain mentions one of `needles` (lowercase)
+pub(crate) fn error_chain_mentions(
+    err: &(dyn std::error::Error + 'static),
+    needles: &[&str],
+) -> bool {
+    let mut current = Some(err);
+    while let Some(e) = current {
+        let message = e.to_string().to_lowercase();
+        if needles.iter().any(|needle| message.contains(needle)) {
+            return true;
+        }
+        current = e.source();
+    }
+    false
+}
+
+// Prints `value` as one line of JSON to stdout, for bins run with `--json`
+pub fn emit_json(value: impl Serialize) -> Result<(), serde_json::Error> {
+    println!("{}", serde_json::to_string(&value)?);
+    Ok(())
+}
+
+// Scalars are stored in the last felt of a word, e.g. the counter is [0, 0, 0, count]
+pub fn word_from_u64(value: u64) -> Word {
+    Word::new([Felt::new(0), Felt::new(0), Felt::new(0), Felt::new(value)])
+}
+
+// Reads a scalar stored with `word_from_u64`
+pub fn word_to_u64(word: &Word) -> u64 {
+    word[3].as_int()
+}
+
+// Renders a word as its four felts
+pub(crate) fn format_word(word: &Word) -> String {
+    let felts: Vec<String> = word.iter().map(|felt| felt.as_int().to_string()).collect();
+    format!("[{}]", felts.join(", "))
+}
@@ -0,0 +1,611 @@
+// Creating, inspecting & consuming notes
+
+use miden_client::{
+    ClientError, Deserializable, Felt, Serializable, Word,
+    account::{Account, AccountId},
+    assembly::CodeBuilder,
+    asset::{Asset, FungibleAsset},
+    block::BlockNumber,
+    crypto::{FeltRng, MerklePath},
+    note::{
+        Note, NoteAssets, NoteError, NoteFile, NoteId, NoteInputs, NoteMetadata, NoteRecipient,
+        NoteScript, NoteTag, NoteType,
+    },
+    store::{InputNoteRecord, NoteFilter},
+    transaction::{
+        OutputNote, PaymentNoteDescription, TransactionId, TransactionRequest,
+        TransactionRequestBuilder, TransactionRequestError, TransactionScript,
+    },
+};
+use miden_protocol::{MAX_INPUT_NOTES_PER_TX, assembly::DefaultSourceManager};
+use miden_standards::note::{NetworkAccountTarget, WellKnownNote};
+use std::{collections::BTreeSet, fmt, fs, path::Path, sync::Arc};
+
+use super::{
+    Client, CommonError,
+    client::sync_state_retry,
+    error_chain_mentions,
+    masm::kernel_assembler,
+    transactions::{submit_and_sync_accounts, submit_and_wait},
+    wait::wait_for_tx,
+};
+
+// Computes the id a note will have, without submitting anything
+pub fn note_id_for(assets: NoteAssets, metadata: NoteMetadata, recipient: NoteRecipient) -> NoteId {
+    Note::new(assets, metadata, recipient).id()
+}
+
+// Builds the tag routing a note to `account_id`, which the network operator matches on
+pub fn note_tag_for_account(account_id: AccountId) -> NoteTag {
+    NoteTag::with_account_target(account_id)
+}
+
+// Creates public note, tagged with `tag` (e.g. `note_tag_for_account` of its consumer)
+pub async fn create_public_note(
+    client: &mut Client,
+    note_code: String,
+    creator_account: Account,
+    tag: NoteTag,
+    assets: NoteAssets,
+    debug: bool,
+) -> Result<Note, Box<dyn std::error::Error>> {
+    let metadata = NoteMetadata::new(creator_account.id(), NoteType::Public, tag);
+
+    submit_note(client, note_code, &creator_account, assets, metadata, debug).await
+}
+
+// Like `create_public_note`, but with an already-built script and its inputs
+pub async fn create_public_note_with_script(
+    client: &mut Client,
+    creator_account: &Account,
+    script: NoteScript,
+    inputs: NoteInputs,
+    assets: NoteAssets,
+    tag: NoteTag,
+    note_type: NoteType,
+) -> Result<Note, Box<dyn std::error::Error>> {
+    ensure_supported_note_type(note_type)?;
+    let metadata = NoteMetadata::new(creator_account.id(), note_type, tag);
+
+    emit_note(client, creator_account, script, inputs, assets, metadata).await
+}
+
+// Rejects `NoteType::Encrypted`, which neither the client nor the node support yet
+pub fn ensure_supported_note_type(note_type: NoteType) -> Result<(), CommonError> {
+    match note_type {
+        NoteType::Public | NoteType::Private => Ok(()),
+        NoteType::Encrypted => Err(CommonError::UnsupportedNoteType(note_type)),
+    }
+}
+
+// Creates a note holding `assets` for `target` until `deadline_block`, then for the sender
+pub async fn create_timelock_note(
+    client: &mut Client,
+    sender: &Account,
+    target: AccountId,
+    assets: NoteAssets,
+    deadline_block: u32,
+    note_type: NoteType,
+) -> Result<Note, Box<dyn std::error::Error>> {
+    ensure_supported_note_type(note_type)?;
+    let note_code = fs::read_to_string(Path::new("./masm/notes/timelock_note.masm"))?;
+    let note_script = CodeBuilder::new().compile_note_script(note_code)?;
+
+    let note_inputs = timelock_note_inputs(target, deadline_block)?;
+    let serial_num = client.rng().draw_word();
+    let recipient = NoteRecipient::new(serial_num, note_script, note_inputs);
+
+    let metadata = NoteMetadata::new(sender.id(), note_type, note_tag_for_account(target));
+    let note = Note::new(assets, metadata, recipient);
+
+    let note_req = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(note.clone())])
+        .build()?;
+    submit_and_wait(client, sender.id(), note_req).await?;
+
+    Ok(note)
+}
+
+// Lays out the timelock note's inputs: [target suffix, target prefix, deadline block]
+pub fn timelock_note_inputs(
+    target: AccountId,
+    deadline_block: u32,
+) -> Result<NoteInputs, NoteError> {
+    NoteInputs::new(vec![
+        target.suffix(),
+        target.prefix().as_felt(),
+        Felt::from(deadline_block),
+    ])
+}
+
+// Deepest path `create_merkle_note` accepts; the note script walks the leaf index with u32 ops
+pub const MAX_MERKLE_NOTE_DEPTH: u8 = 32;
+
+// A leaf of a Merkle tree with the path opening it, i.e. its siblings from the leaf up
+pub struct MerkleOpening {
+    pub leaf: Word,
+    pub index: u64,
+    pub proof: MerklePath,
+}
+
+impl MerkleOpening {
+    // Root the path opens the leaf to
+    pub fn root(&self) -> Result<Word, CommonError> {
+        self.proof
+            .compute_root(self.index, self.leaf)
+            .map_err(|e| CommonError::InvalidMerkleProof(e.to_string()))
+    }
+}
+
+// Creates a note holding `assets` that only `target` can consume, with a valid merkle `opening`
+pub async fn create_merkle_note(
+    client: &mut Client,
+    sender: &Account,
+    target: AccountId,
+    opening: &MerkleOpening,
+    assets: NoteAssets,
+    note_type: NoteType,
+) -> Result<Note, Box<dyn std::error::Error>> {
+    ensure_supported_note_type(note_type)?;
+    let note_inputs = merkle_note_inputs(target, opening)?;
+
+    let note_code = fs::read_to_string(Path::new("./masm/notes/merkle_note.masm"))?;
+    let note_script = CodeBuilder::new().compile_note_script(note_code)?;
+    let serial_num = client.rng().draw_word();
+    let recipient = NoteRecipient::new(serial_num, note_script, note_inputs);
+
+    let metadata = NoteMetadata::new(sender.id(), note_type, note_tag_for_account(target));
+    let note = Note::new(assets, metadata, recipient);
+
+    let note_req = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(note.clone())])
+        .build()?;
+    submit_and_wait(client, sender.id(), note_req).await?;
+
+    Ok(note)
+}
+
+// Lays out the merkle note's inputs: [target suffix, prefix, index, depth, ROOT, LEAF, path]
+pub fn merkle_note_inputs(
+    target: AccountId,
+    opening: &MerkleOpening,
+) -> Result<NoteInputs, CommonError> {
+    let depth = opening.proof.depth();
+    if depth > MAX_MERKLE_NOTE_DEPTH {
+        return Err(CommonError::InvalidMerkleProof(format!(
+            "depth {} exceeds the supported {}",
+            depth, MAX_MERKLE_NOTE_DEPTH
+        )));
+    }
+    let root = opening.root()?;
+
+    let mut inputs = vec![
+        target.suffix(),
+        target.prefix().as_felt(),
+        Felt::new(opening.index),
+        Felt::from(depth),
+    ];
+    inputs.extend(root.iter());
+    inputs.extend(opening.leaf.iter());
+    for node in opening.proof.nodes() {
+        inputs.extend(node.iter());
+    }
+
+    NoteInputs::new(inputs).map_err(|e| CommonError::InvalidMerkleProof(e.to_string()))
+}
+
+// Creates a public note for a network account, with the execution hint carried by `target`
+pub async fn create_public_note_hinted(
+    client: &mut Client,
+    note_code: String,
+    creator_account: Account,
+    target: NetworkAccountTarget,
+    tag: NoteTag,
+    assets: NoteAssets,
+    debug: bool,
+) -> Result<Note, Box<dyn std::error::Error>> {
+    let metadata = NoteMetadata::new(creator_account.id(), NoteType::Public, tag)
+        .with_attachment(target.into());
+
+    submit_note(client, note_code, &creator_account, assets, metadata, debug).await
+}
+
+// Assembles the note script and submits a transaction emitting the note from the creator
+async fn submit_note(
+    client: &mut Client,
+    note_code: String,
+    creator_account: &Account,
+    assets: NoteAssets,
+    metadata: NoteMetadata,
+    debug: bool,
+) -> Result<Note, Box<dyn std::error::Error>> {
+    let source_manager = Arc::new(DefaultSourceManager::default());
+    let assembler = kernel_assembler(debug, &source_manager);
+    let program = assembler.clone().assemble_program(note_code)?;
+    let note_script = NoteScript::new(program);
+    let note_inputs = NoteInputs::new([].to_vec())?;
+
+    emit_note(
+        client,
+        creator_account,
+        note_script,
+        note_inputs,
+        assets,
+        metadata,
+    )
+    .await
+}
+
+// Submits a transaction emitting a note with an already-built script from the creator
+async fn emit_note(
+    client: &mut Client,
+    creator_account: &Account,
+    note_script: NoteScript,
+    note_inputs: NoteInputs,
+    assets: NoteAssets,
+    metadata: NoteMetadata,
+) -> Result<Note, Box<dyn std::error::Error>> {
+    let serial_num = client.rng().draw_word();
+    let recipient = NoteRecipient::new(serial_num, note_script, note_inputs);
+
+    let note = Note::new(assets, metadata, recipient);
+
+    let note_req = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(note.clone())])
+        .build()?;
+
+    let tx_id = client
+        .submit_new_transaction(creator_account.id(), note_req)
+        .await?;
+
+    wait_for_tx(client, tx_id).await?;
+
+    Ok(note)
+}
+
+// Builds note assets from (faucet_id, amount) pairs, one fungible asset per faucet
+pub fn note_assets(entries: &[(AccountId, u64)]) -> Result<NoteAssets, Box<dyn std::error::Error>> {
+    if entries.len() > NoteAssets::MAX_NUM_ASSETS {
+        return Err(CommonError::TooManyAssets {
+            count: entries.len(),
+            max: NoteAssets::MAX_NUM_ASSETS,
+        }
+        .into());
+    }
+
+    let assets = entries
+        .iter()
+        .map(|(faucet_id, amount)| FungibleAsset::new(*faucet_id, *amount).map(Asset::from))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(NoteAssets::new(assets)?)
+}
+
+// Writes the serialized note to a file so it can be handed to the recipient out of band
+pub fn export_note(note: &Note, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    fs::write(path, note.to_bytes())?;
+    Ok(())
+}
+
+// Reads a note exported with `export_note` and imports it into the client
+pub async fn import_note_from_file(
+    client: &mut Client,
+    path: &Path,
+) -> Result<NoteId, Box<dyn std::error::Error>> {
+    let bytes = fs::read(path)?;
+    let note = Note::read_from_bytes(&bytes).map_err(|e| CommonError::InvalidNoteFile {
+        path: path.to_path_buf(),
+        reason: e.to_string(),
+    })?;
+
+    let note_file = NoteFile::NoteDetails {
+        details: note.clone().into(),
+        after_block_num: BlockNumber::GENESIS,
+        tag: Some(note.metadata().tag()),
+    };
+    let note_ids = client.import_notes(&[note_file]).await?;
+
+    Ok(note_ids.first().copied().unwrap_or(note.id()))
+}
+
+// Commitment to the recipient's serial number, script & inputs, as the chain reports it
+pub fn recipient_digest(recipient: &NoteRecipient) -> Word {
+    recipient.digest()
+}
+
+// Decodes the account a P2ID note pays to from its [target_suffix, target_prefix] inputs
+pub fn p2id_target(note: &Note) -> Result<AccountId, Box<dyn std::error::Error>> {
+    let inputs = note.inputs().values();
+    if note.script().root() != WellKnownNote::P2ID.script_root() || inputs.len() != 2 {
+        return Err(CommonError::NotAP2idNote(note.id()).into());
+    }
+
+    Ok(AccountId::try_from([inputs[1], inputs[0]])?)
+}
+
+// Checks whether the note's script commitment equals the expected script
+pub fn note_script_matches(note: &Note, expected: &NoteScript) -> bool {
+    note.script().root() == expected.root()
+}
+
+// Errors if the note's script commitment differs from the expected script
+pub fn assert_note_script(note: &Note, expected: &NoteScript) -> Result<(), CommonError> {
+    if note_script_matches(note, expected) {
+        return Ok(());
+    }
+
+    Err(CommonError::NoteScriptMismatch {
+        note_id: note.id(),
+        expected: expected.root(),
+        actual: note.script().root(),
+    })
+}
+
+// What a note carries, for display; sender & type are `None` without the note's metadata
+#[derive(Debug, Clone)]
+pub struct NoteSummary {
+    pub id: NoteId,
+    pub sender: Option<AccountId>,
+    pub note_type: Option<NoteType>,
+    // (faucet_id, amount) for each fungible asset
+    pub assets: Vec<(AccountId, u64)>,
+    pub non_fungible_assets: usize,
+    pub inputs: Vec<Felt>,
+}
+
+impl fmt::Display for NoteSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "note {}", self.id.to_hex())?;
+        match (self.sender, self.note_type) {
+            (Some(sender), Some(note_type)) => {
+                write!(f, " from {} ({:?})", sender.to_hex(), note_type)?
+            }
+            _ => write!(f, " from unknown sender")?,
+        }
+        writeln!(f, ":")?;
+        for (faucet_id, amount) in &self.assets {
+            writeln!(f, "  {} of faucet {}", amount, faucet_id.to_hex())?;
+        }
+        if self.non_fungible_assets > 0 {
+            writeln!(f, "  {} non-fungible assets", self.non_fungible_assets)?;
+        }
+        let inputs: Vec<String> = self.inputs.iter().map(|felt| felt.to_string()).collect();
+        writeln!(f, "  inputs: [{}]", inputs.join(", "))
+    }
+}
+
+// Decodes a stored note's sender, type, assets & inputs
+pub fn describe_note(record: &InputNoteRecord) -> NoteSummary {
+    let mut assets = Vec::new();
+    let mut non_fungible_assets = 0;
+    for asset in record.assets().iter() {
+        match asset {
+            Asset::Fungible(asset) => assets.push((asset.faucet_id(), asset.amount())),
+            Asset::NonFungible(_) => non_fungible_assets += 1,
+        }
+    }
+
+    NoteSummary {
+        id: record.id(),
+        sender: record.metadata().map(|metadata| metadata.sender()),
+        note_type: record.metadata().map(|metadata| metadata.note_type()),
+        assets,
+        non_fungible_assets,
+        inputs: record.details().inputs().values().to_vec(),
+    }
+}
+
+// Lists the stored input notes carrying `tag`, skipping those without metadata
+pub async fn notes_by_tag(
+    client: &mut Client,
+    tag: NoteTag,
+) -> Result<Vec<InputNoteRecord>, ClientError> {
+    let notes = client.get_input_notes(NoteFilter::All).await?;
+
+    Ok(notes
+        .into_iter()
+        .filter(|record| {
+            record
+                .metadata()
+                .is_some_and(|metadata| metadata.tag() == tag)
+        })
+        .collect())
+}
+
+// Loads the full notes for the given ids from the client's store
+pub async fn get_notes_by_id(
+    client: &mut Client,
+    note_ids: &[NoteId],
+) -> Result<Vec<Note>, Box<dyn std::error::Error>> {
+    let mut notes = Vec::with_capacity(note_ids.len());
+    for note_id in note_ids {
+        let record = client
+            .get_input_note(*note_id)
+            .await?
+            .ok_or(CommonError::NoteNotFound(*note_id))?;
+        notes.push(record.try_into()?);
+    }
+    Ok(notes)
+}
+
+// Consumes a note, passing `args` to its script, then waits for the transaction
+pub async fn consume_note_with_args(
+    client: &mut Client,
+    consumer_id: AccountId,
+    note_id: NoteId,
+    args: Word,
+) -> Result<TransactionId, Box<dyn std::error::Error>> {
+    let notes = get_notes_by_id(client, &[note_id]).await?;
+    let request = TransactionRequestBuilder::new()
+        .input_notes(notes.into_iter().map(|note| (note, Some(args))))
+        .build()?;
+
+    Ok(submit_and_wait(client, consumer_id, request).await?)
+}
+
+// Consumes the given notes and emits new ones in a single transaction, then waits for it
+pub async fn create_consume_and_emit_tx(
+    client: &mut Client,
+    account_id: AccountId,
+    consume: Vec<NoteId>,
+    emit: Vec<OutputNote>,
+    script: Option<TransactionScript>,
+) -> Result<TransactionId, Box<dyn std::error::Error>> {
+    let input_notes = get_notes_by_id(client, &consume).await?;
+    let builder =
+        TransactionRequestBuilder::new().input_notes(input_notes.into_iter().map(|n| (n, None)));
+
+    let builder = match script {
+        // A custom script creates the emitted notes itself, so they're only expected outputs
+        Some(script) => {
+            let recipients = emit
+                .iter()
+                .filter_map(|note| match note {
+                    OutputNote::Full(note) => Some(note.recipient().clone()),
+                    _ => None,
+                })
+                .collect();
+            builder
+                .custom_script(script)
+                .expected_output_recipients(recipients)
+        }
+        None => builder.own_output_notes(emit),
+    };
+
+    let request = builder.build()?;
+    Ok(submit_and_wait(client, account_id, request).await?)
+}
+
+// Sends `assets` from `sender` to `recipient` in a P2ID note, then consumes it as the recipient
+pub async fn transfer_and_settle(
+    client: &mut Client,
+    sender: &Account,
+    recipient: &Account,
+    assets: Vec<Asset>,
+    note_type: NoteType,
+) -> Result<(TransactionId, TransactionId), Box<dyn std::error::Error>> {
+    ensure_supported_note_type(note_type)?;
+    let payment = PaymentNoteDescription::new(assets, sender.id(), recipient.id());
+    let send_request =
+        TransactionRequestBuilder::new().build_pay_to_id(payment, note_type, client.rng())?;
+    let p2id_notes = send_request.expected_output_own_notes();
+
+    // The note is committed in the same block as the transaction creating it
+    let send_tx_id = submit_and_wait(client, sender.id(), send_request).await?;
+    eprintln!("p2id note sent in {}", send_tx_id.to_hex());
+
+    let consume_request = TransactionRequestBuilder::new().build_consume_notes(p2id_notes)?;
+    let consume_tx_id =
+        submit_and_sync_accounts(client, recipient.id(), consume_request, &[sender.id()]).await?;
+    eprintln!("p2id note consumed in {}", consume_tx_id.to_hex());
+
+    Ok((send_tx_id, consume_tx_id))
+}
+
+// Consumes every note currently consumable by the account, in batches of MAX_INPUT_NOTES_PER_TX
+pub async fn consume_all_consumable(
+    client: &mut Client,
+    account_id: AccountId,
+) -> Result<Vec<TransactionId>, Box<dyn std::error::Error>> {
+    let consumable = client.get_consumable_notes(Some(account_id)).await?;
+
+    let mut notes = Vec::with_capacity(consumable.len());
+    for (record, _) in consumable {
+        eprint!("{}", describe_note(&record));
+        notes.push(record.try_into()?);
+    }
+
+    consume_in_batches(client, account_id, notes).await
+}
+
+// Like `submit_and_wait`, but reports notes consumed by someone else as `AlreadyConsumed`
+async fn submit_consume(
+    client: &mut Client,
+    account_id: AccountId,
+    request: TransactionRequest,
+    note_ids: &[NoteId],
+) -> Result<TransactionId, Box<dyn std::error::Error>> {
+    let err = match submit_and_wait(client, account_id, request).await {
+        Ok(tx_id) => return Ok(tx_id),
+        Err(err) => err,
+    };
+
+    if let Some(note_id) = already_consumed_note(&err) {
+        return Err(CommonError::AlreadyConsumed(note_id).into());
+    }
+    if error_chain_mentions(&err, &["already spent", "nullifier spent"]) {
+        // The node saw the consumption before this client did; sync to find which note it was
+        sync_state_retry(client, 3).await?;
+        for &note_id in note_ids {
+            if let Some(record) = client.get_input_note(note_id).await?
+                && record.is_consumed()
+            {
+                return Err(CommonError::AlreadyConsumed(note_id).into());
+            }
+        }
+    }
+
+    Err(err.into())
+}
+
+// The note a request was refused for because the client's store has it consumed already
+pub fn already_consumed_note(err: &ClientError) -> Option<NoteId> {
+    match err {
+        ClientError::TransactionRequestError(
+            TransactionRequestError::InputNoteAlreadyConsumed(note_id),
+        ) => Some(*note_id),
+        _ => None,
+    }
+}
+
+// Consumes `note_ids` into `consumer` whatever their senders, in as few transactions as allowed
+pub async fn consume_from_many(
+    client: &mut Client,
+    consumer: AccountId,
+    note_ids: Vec<NoteId>,
+) -> Result<Vec<TransactionId>, Box<dyn std::error::Error>> {
+    sync_state_retry(client, 3).await?;
+    let consumable: BTreeSet<NoteId> = client
+        .get_consumable_notes(Some(consumer))
+        .await?
+        .into_iter()
+        .map(|(record, _)| record.id())
+        .collect();
+
+    let mut seen = BTreeSet::new();
+    let note_ids: Vec<NoteId> = note_ids.into_iter().filter(|id| seen.insert(*id)).collect();
+    let not_consumable: Vec<NoteId> = note_ids
+        .iter()
+        .filter(|id| !consumable.contains(id))
+        .copied()
+        .collect();
+    if !not_consumable.is_empty() {
+        return Err(CommonError::NotConsumable {
+            account_id: consumer,
+            note_ids: not_consumable,
+        }
+        .into());
+    }
+
+    let notes = get_notes_by_id(client, &note_ids).await?;
+    consume_in_batches(client, consumer, notes).await
+}
+
+// Submits one consume transaction per MAX_INPUT_NOTES_PER_TX notes
+async fn consume_in_batches(
+    client: &mut Client,
+    account_id: AccountId,
+    notes: Vec<Note>,
+) -> Result<Vec<TransactionId>, Box<dyn std::error::Error>> {
+    let mut tx_ids = Vec::new();
+
+    for batch in notes.chunks(MAX_INPUT_NOTES_PER_TX) {
+        let note_ids: Vec<NoteId> = batch.iter().map(Note::id).collect();
+        let request = TransactionRequestBuilder::new().build_consume_notes(batch.to_vec())?;
+        let tx_id = submit_consume(client, account_id, request, &note_ids).await?;
+        eprintln!("consumed {} notes in {}", batch.len(), tx_id.to_hex());
+        tx_ids.push(tx_id);
+    }
+
+    Ok(tx_ids)
+}
@@ -0,0 +1,521 @@
+// Reading, writing & comparing account storage
+
+use miden_client::{
+    Word,
+    account::{Account, AccountDelta, AccountId, StorageSlotContent, StorageSlotName},
+    asset::{Asset, FungibleAsset},
+    note::{NoteAssets, NoteType},
+    transaction::{ExecutedTransaction, TransactionId, TransactionRequestBuilder},
+};
+use miden_protocol::assembly::Library;
+use miden_standards::account::faucets::{BasicFungibleFaucet, FungibleFaucetExt};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt, fs,
+    path::Path,
+};
+
+use super::{
+    Client, CommonError,
+    client::sync_state_retry,
+    format_word,
+    masm::{create_tx_script, procedure_roots},
+    notes::ensure_supported_note_type,
+    transactions::submit_and_wait,
+};
+
+// Returns the account delta produced by an executed transaction
+pub fn account_delta(tx: &ExecutedTransaction) -> AccountDelta {
+    tx.account_delta().clone()
+}
+
+// Prints the storage & vault changes of an executed transaction
+pub fn print_account_delta(tx: &ExecutedTransaction) {
+    let delta = tx.account_delta();
+    let initial_storage = tx.initial_account().storage().header();
+
+    println!("account delta for {}:", delta.id().to_hex());
+    println!("  nonce: +{}", delta.nonce_delta().as_int());
+
+    for (slot_name, new_value) in delta.storage().values() {
+        let old_value = initial_storage
+            .find_slot_header_by_name(slot_name)
+            .map(|slot| format_word(&slot.value()))
+            .unwrap_or_else(|| "<new>".to_string());
+        println!(
+            "  {}: {} -> {}",
+            slot_name,
+            old_value,
+            format_word(new_value)
+        );
+    }
+
+    for (slot_name, map_delta) in delta.storage().maps() {
+        for (key, value) in map_delta.entries() {
+            println!(
+                "  {}[{}]: -> {}",
+                slot_name,
+                format_word(key.inner()),
+                format_word(value)
+            );
+        }
+    }
+
+    for (faucet_id, amount) in delta.vault().fungible().iter() {
+        println!("  vault {}: {:+}", faucet_id.to_hex(), amount);
+    }
+
+    for asset in delta.vault().non_fungible().iter().map(|(asset, _)| asset) {
+        println!("  vault non-fungible changed: {:?}", asset);
+    }
+}
+
+// Lists every storage slot of an account, map slots as their root then one `slot[key]` per entry
+pub fn dump_storage(account: &Account) -> Vec<(String, Word)> {
+    let mut entries = Vec::new();
+
+    for slot in account.storage().slots() {
+        match slot.content() {
+            StorageSlotContent::Value(value) => {
+                entries.push((slot.name().to_string(), *value));
+            }
+            StorageSlotContent::Map(map) => {
+                entries.push((format!("{} (map root)", slot.name()), map.root()));
+                for (key, value) in map.entries() {
+                    entries.push((format!("{}[{}]", slot.name(), format_word(key)), *value));
+                }
+            }
+        }
+    }
+
+    entries
+}
+
+// Checks that `account` declares every slot in `expected_slots`
+pub fn verify_contract_layout(
+    account: &Account,
+    expected_slots: &[&str],
+) -> Result<(), CommonError> {
+    let found: Vec<String> = account
+        .storage()
+        .slots()
+        .iter()
+        .map(|slot| slot.name().to_string())
+        .collect();
+    let missing: Vec<String> = expected_slots
+        .iter()
+        .filter(|expected| !found.iter().any(|name| name == *expected))
+        .map(|expected| expected.to_string())
+        .collect();
+
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(CommonError::UnexpectedStorageLayout {
+            account_id: account.id(),
+            missing,
+            found,
+        })
+    }
+}
+
+// Overview of a compiled contract: its exported procedures and the deployed account's storage
+#[derive(Debug, Clone)]
+pub struct ContractDescription {
+    // (path, MAST root) of each exported procedure
+    pub procedures: Vec<(String, Word)>,
+    pub account_id: Option<AccountId>,
+    // Slot names & current values as listed by `dump_storage`
+    pub storage: Vec<(String, Word)>,
+}
+
+impl fmt::Display for ContractDescription {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "procedures:")?;
+        for (path, root) in &self.procedures {
+            writeln!(f, "  {} {}", path, root.to_hex())?;
+        }
+        match self.account_id {
+            Some(account_id) => writeln!(f, "storage of {}:", account_id.to_hex())?,
+            None => return writeln!(f, "storage: unknown without an account"),
+        }
+        for (name, value) in &self.storage {
+            writeln!(f, "  {} = {}", name, format_word(value))?;
+        }
+        Ok(())
+    }
+}
+
+// Describes a contract before interacting with it; storage is only listed for a given `account`
+pub fn describe_contract(library: &Library, account: Option<&Account>) -> ContractDescription {
+    ContractDescription {
+        procedures: procedure_roots(library),
+        account_id: account.map(Account::id),
+        storage: account.map(dump_storage).unwrap_or_default(),
+    }
+}
+
+// Point-in-time copy of an account's nonce, storage & fungible balances
+#[derive(Debug, Clone)]
+pub struct AccountSnapshot {
+    pub id: AccountId,
+    pub nonce: u64,
+    pub storage: BTreeMap<String, Word>,
+    pub balances: BTreeMap<AccountId, u64>,
+}
+
+// Changes between two snapshots of the same account
+#[derive(Debug, Clone)]
+pub struct AccountDiff {
+    pub id: AccountId,
+    pub nonce_before: u64,
+    pub nonce_after: u64,
+    // (slot, before, after); `None` when the entry doesn't exist on that side
+    pub storage: Vec<(String, Option<Word>, Option<Word>)>,
+    pub balances: Vec<(AccountId, i128)>,
+}
+
+impl fmt::Display for AccountDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "changes for {}:", self.id.to_hex())?;
+        writeln!(f, "  nonce: {} -> {}", self.nonce_before, self.nonce_after)?;
+        let show = |word: &Option<Word>| word.as_ref().map_or("none".to_string(), format_word);
+        for (slot, before, after) in &self.storage {
+            writeln!(f, "  {}: {} -> {}", slot, show(before), show(after))?;
+        }
+        for (faucet_id, amount) in &self.balances {
+            writeln!(f, "  vault {}: {:+}", faucet_id.to_hex(), amount)?;
+        }
+        Ok(())
+    }
+}
+
+// Captures an account's state, to be compared later with `diff_snapshots`
+pub fn snapshot_account(account: &Account) -> AccountSnapshot {
+    let balances = account
+        .vault()
+        .assets()
+        .filter_map(|asset| match asset {
+            Asset::Fungible(asset) => Some((asset.faucet_id(), asset.amount())),
+            Asset::NonFungible(_) => None,
+        })
+        .collect();
+
+    AccountSnapshot {
+        id: account.id(),
+        nonce: account.nonce().as_int(),
+        storage: dump_storage(account).into_iter().collect(),
+        balances,
+    }
+}
+
+// Lists what changed between two snapshots; unchanged slots & balances are left out
+pub fn diff_snapshots(before: &AccountSnapshot, after: &AccountSnapshot) -> AccountDiff {
+    let slots: BTreeSet<&String> = before.storage.keys().chain(after.storage.keys()).collect();
+    let storage = slots
+        .into_iter()
+        .filter_map(|slot| {
+            let old = before.storage.get(slot).copied();
+            let new = after.storage.get(slot).copied();
+            (old != new).then(|| (slot.clone(), old, new))
+        })
+        .collect();
+
+    let faucets: BTreeSet<&AccountId> = before
+        .balances
+        .keys()
+        .chain(after.balances.keys())
+        .collect();
+    let balances = faucets
+        .into_iter()
+        .filter_map(|faucet_id| {
+            let old = before.balances.get(faucet_id).copied().unwrap_or(0) as i128;
+            let new = after.balances.get(faucet_id).copied().unwrap_or(0) as i128;
+            (old != new).then_some((*faucet_id, new - old))
+        })
+        .collect();
+
+    AccountDiff {
+        id: after.id,
+        nonce_before: before.nonce,
+        nonce_after: after.nonce,
+        storage,
+        balances,
+    }
+}
+
+// Checks that two clients hold the same state for an account, erroring with the diff
+pub async fn assert_accounts_agree(
+    client_a: &Client,
+    client_b: &Client,
+    account_id: AccountId,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let account_a: Account = client_a.try_get_account(account_id).await?.try_into()?;
+    let account_b: Account = client_b.try_get_account(account_id).await?.try_into()?;
+
+    if account_a.storage().to_commitment() == account_b.storage().to_commitment()
+        && account_a.nonce() == account_b.nonce()
+    {
+        return Ok(());
+    }
+
+    Err(CommonError::AccountsDisagree {
+        account_id,
+        diff: diff_snapshots(&snapshot_account(&account_a), &snapshot_account(&account_b)),
+    }
+    .into())
+}
+
+// On-disk JSON form of `AccountSnapshot`, with words & ids as hex
+#[derive(Serialize, Deserialize)]
+struct SnapshotFile {
+    account_id: String,
+    nonce: u64,
+    storage: BTreeMap<String, String>,
+    balances: BTreeMap<String, u64>,
+}
+
+// Writes the account's storage & fungible balances to a JSON file, for `assert_account_matches`
+pub fn save_account_state(
+    account: &Account,
+    path: &Path,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let snapshot = snapshot_account(account);
+    let file = SnapshotFile {
+        account_id: snapshot.id.to_hex(),
+        nonce: snapshot.nonce,
+        storage: snapshot
+            .storage
+            .iter()
+            .map(|(name, value)| (name.clone(), value.to_hex()))
+            .collect(),
+        balances: snapshot
+            .balances
+            .iter()
+            .map(|(faucet_id, amount)| (faucet_id.to_hex(), *amount))
+            .collect(),
+    };
+
+    fs::write(path, serde_json::to_string_pretty(&file)?)?;
+    Ok(())
+}
+
+// Checks the account's storage & balances against the state `save_account_state` wrote
+pub fn assert_account_matches(account: &Account, path: &Path) -> Result<(), CommonError> {
+    let invalid = |reason: String| CommonError::InvalidSnapshot {
+        path: path.to_path_buf(),
+        reason,
+    };
+
+    let json = fs::read_to_string(path).map_err(|e| invalid(e.to_string()))?;
+    let file: SnapshotFile = serde_json::from_str(&json).map_err(|e| invalid(e.to_string()))?;
+
+    let saved_id = AccountId::from_hex(&file.account_id).map_err(|e| invalid(e.to_string()))?;
+    if saved_id != account.id() {
+        return Err(invalid(format!(
+            "it is for account {}, not {}",
+            saved_id.to_hex(),
+            account.id().to_hex()
+        )));
+    }
+
+    let mut storage = BTreeMap::new();
+    for (name, value) in file.storage {
+        let value = Word::try_from(value.as_str()).map_err(|e| invalid(e.to_string()))?;
+        storage.insert(name, value);
+    }
+    let mut balances = BTreeMap::new();
+    for (faucet_id, amount) in file.balances {
+        let faucet_id = AccountId::from_hex(&faucet_id).map_err(|e| invalid(e.to_string()))?;
+        balances.insert(faucet_id, amount);
+    }
+    let saved = AccountSnapshot {
+        id: saved_id,
+        nonce: file.nonce,
+        storage,
+        balances,
+    };
+
+    let diff = diff_snapshots(&saved, &snapshot_account(account));
+    if diff.storage.is_empty() && diff.balances.is_empty() {
+        return Ok(());
+    }
+
+    Err(CommonError::StateMismatch {
+        path: path.to_path_buf(),
+        diff,
+    })
+}
+
+// Reads a value slot
+pub fn read_storage_value(
+    account: &Account,
+    slot_name: &str,
+) -> Result<Word, Box<dyn std::error::Error>> {
+    let storage_slot_name = StorageSlotName::new(slot_name)?;
+    let slot = account
+        .storage()
+        .get(&storage_slot_name)
+        .ok_or_else(|| CommonError::StorageSlotNotFound(slot_name.to_string()))?;
+
+    match slot.content() {
+        StorageSlotContent::Value(value) => Ok(*value),
+        StorageSlotContent::Map(_) => Err(CommonError::NotAValueSlot(slot_name.to_string()).into()),
+    }
+}
+
+// Reads a single entry from a storage map slot
+pub fn read_storage_map(
+    account: &Account,
+    slot_name: &str,
+    key: Word,
+) -> Result<Word, Box<dyn std::error::Error>> {
+    let storage_slot_name = StorageSlotName::new(slot_name)?;
+    let slot = account
+        .storage()
+        .get(&storage_slot_name)
+        .ok_or_else(|| CommonError::StorageSlotNotFound(slot_name.to_string()))?;
+
+    match slot.content() {
+        StorageSlotContent::Map(map) => Ok(map.get(&key)),
+        StorageSlotContent::Value(_) => Err(CommonError::NotAMapSlot(slot_name.to_string()).into()),
+    }
+}
+
+// Writes `value` into a value slot through the `set_item` procedure `setter` exports
+pub async fn set_storage_slot(
+    client: &mut Client,
+    account_id: AccountId,
+    setter: &Library,
+    slot_name: &str,
+    value: Word,
+) -> Result<TransactionId, Box<dyn std::error::Error>> {
+    let storage_slot_name = StorageSlotName::new(slot_name)?;
+    set_storage_slots(client, account_id, setter, vec![(storage_slot_name, value)]).await
+}
+
+// Like `set_storage_slot`, but writes all `updates` from one script in a single transaction
+pub async fn set_storage_slots(
+    client: &mut Client,
+    account_id: AccountId,
+    setter: &Library,
+    updates: Vec<(StorageSlotName, Word)>,
+) -> Result<TransactionId, Box<dyn std::error::Error>> {
+    let account: Account = client.try_get_account(account_id).await?.try_into()?;
+    for (storage_slot_name, _) in &updates {
+        let slot = account
+            .storage()
+            .get(storage_slot_name)
+            .ok_or_else(|| CommonError::StorageSlotNotFound(storage_slot_name.to_string()))?;
+        if !matches!(slot.content(), StorageSlotContent::Value(_)) {
+            return Err(CommonError::NotAValueSlot(storage_slot_name.to_string()).into());
+        }
+    }
+
+    let (setter_path, _) = procedure_roots(setter)
+        .into_iter()
+        .find(|(path, root)| path.ends_with("::set_item") && account.code().has_procedure(*root))
+        .ok_or_else(|| CommonError::MissingProcedure {
+            account_id,
+            procedure: "set_item".to_string(),
+        })?;
+    let module_path = setter_path.trim_end_matches("::set_item");
+    let module_name = module_path.rsplit("::").next().unwrap_or(module_path);
+
+    let mut script_code = format!("use {module_path}\nbegin\n");
+    for (storage_slot_name, value) in &updates {
+        let slot_id = storage_slot_name.id();
+        script_code.push_str(&format!(
+            "    push.{value}\n    push.{}.{}\n    call.{module_name}::set_item\n    dropw drop drop\n",
+            slot_id.suffix(),
+            slot_id.prefix(),
+        ));
+    }
+    script_code.push_str("end\n");
+    let tx_script = create_tx_script(script_code, Some(setter.clone()))?;
+
+    let request = TransactionRequestBuilder::new()
+        .custom_script(tx_script)
+        .build()?;
+
+    Ok(submit_and_wait(client, account_id, request).await?)
+}
+
+// Checks a basic fungible faucet can still mint `amount` without going over its `max_supply`
+pub fn check_mint_supply(faucet: &Account, amount: u64) -> Result<(), Box<dyn std::error::Error>> {
+    let max_supply = BasicFungibleFaucet::try_from(faucet)?.max_supply().as_int();
+    let issued = faucet.get_token_issuance()?.as_int();
+
+    if issued.saturating_add(amount) > max_supply {
+        return Err(CommonError::ExceedsMaxSupply {
+            faucet_id: faucet.id(),
+            requested: amount,
+            issued,
+            max_supply,
+        }
+        .into());
+    }
+    Ok(())
+}
+
+// Mints `amount` to `target_id` in a P2ID note, rejecting mints over the faucet's max supply
+pub async fn mint_checked(
+    client: &mut Client,
+    faucet_id: AccountId,
+    target_id: AccountId,
+    amount: u64,
+    note_type: NoteType,
+) -> Result<TransactionId, Box<dyn std::error::Error>> {
+    ensure_supported_note_type(note_type)?;
+    sync_state_retry(client, 3).await?;
+
+    let faucet: Account = client.try_get_account(faucet_id).await?.try_into()?;
+    check_mint_supply(&faucet, amount)?;
+
+    let asset = FungibleAsset::new(faucet_id, amount)?;
+    let request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+        asset,
+        target_id,
+        note_type,
+        client.rng(),
+    )?;
+
+    Ok(submit_and_wait(client, faucet_id, request).await?)
+}
+
+// Checks whether the account's vault holds every asset in `assets`
+pub fn can_afford(account: &Account, assets: &NoteAssets) -> bool {
+    ensure_affordable(account, assets).is_ok()
+}
+
+// Errors with the first asset the account's vault can't cover
+pub fn ensure_affordable(account: &Account, assets: &NoteAssets) -> Result<(), CommonError> {
+    let mut requested: BTreeMap<AccountId, u64> = BTreeMap::new();
+    for asset in assets.iter_fungible() {
+        *requested.entry(asset.faucet_id()).or_default() += asset.amount();
+    }
+
+    for (faucet_id, requested) in requested {
+        let available = account.vault().get_balance(faucet_id).unwrap_or(0);
+        if available < requested {
+            return Err(CommonError::InsufficientBalance {
+                faucet_id,
+                requested,
+                available,
+            });
+        }
+    }
+
+    for asset in assets.iter_non_fungible() {
+        if !account
+            .vault()
+            .has_non_fungible_asset(asset)
+            .unwrap_or(false)
+        {
+            return Err(CommonError::MissingNonFungibleAsset(asset));
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,751 @@
+// Building, executing & submitting transactions
+
+use miden_client::{
+    ClientError, Felt, RemoteTransactionProver, Word,
+    account::{Account, AccountId},
+    asset::{Asset, FungibleAsset},
+    block::BlockNumber,
+    builder::{BuilderAuthenticator, ClientBuilder},
+    note::{Note, NoteId, NoteRecipient, NoteType},
+    rpc::{GrpcError, NodeRpcClientEndpoint, RpcError},
+    store::TransactionFilter,
+    transaction::{
+        DiscardCause, ExecutedTransaction, LocalTransactionProver, OutputNote,
+        PaymentNoteDescription, TransactionId, TransactionProver, TransactionRequest,
+        TransactionRequestBuilder, TransactionResult, TransactionScript, TransactionScriptTemplate,
+        TransactionStatus,
+    },
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tokio::{
+    sync::watch,
+    time::{Duration, Instant, sleep},
+};
+
+use super::{
+    Client, CommonError,
+    client::{ClientConfig, build_rpc, sync_state_retry},
+    error_chain_mentions, format_word,
+    masm::{COUNTER_LIBRARY_PATH, create_library, create_tx_script},
+    notes::get_notes_by_id,
+    storage::check_mint_supply,
+    wait::{WaitError, wait_for_tx, wait_for_tx_cancellable},
+};
+
+// Executes a transaction locally without proving or submitting it
+pub async fn execute_local(
+    client: &mut Client,
+    account_id: AccountId,
+    request: TransactionRequest,
+) -> Result<ExecutedTransaction, ClientError> {
+    let tx_result = client.execute_transaction(account_id, request).await?;
+    Ok(tx_result.executed_transaction().clone())
+}
+
+// Fee charged by an executed transaction, paid in the native asset of the network
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeEstimate {
+    pub faucet_id: AccountId,
+    pub amount: u64,
+}
+
+impl From<&ExecutedTransaction> for FeeEstimate {
+    fn from(tx: &ExecutedTransaction) -> Self {
+        let fee = tx.fee();
+        Self {
+            faucet_id: fee.faucet_id(),
+            amount: fee.amount(),
+        }
+    }
+}
+
+impl fmt::Display for FeeEstimate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} of faucet {}", self.amount, self.faucet_id.to_hex())
+    }
+}
+
+// Executes a transaction locally and reports the fee it would be charged, without submitting
+pub async fn estimate_fee(
+    client: &mut Client,
+    account_id: AccountId,
+    request: TransactionRequest,
+) -> Result<FeeEstimate, ClientError> {
+    let executed_tx = execute_local(client, account_id, request).await?;
+    Ok(FeeEstimate::from(&executed_tx))
+}
+
+// Debug log of each request the submit helpers send, to stderr when `DEBUG_REQUESTS` is set
+fn log_request(request: &TransactionRequest) {
+    if std::env::var_os("DEBUG_REQUESTS").is_some() {
+        eprintln!("{}", describe_request(request));
+    }
+}
+
+// Summarizes what a transaction request contains, to check it was built as intended
+pub fn describe_request(request: &TransactionRequest) -> String {
+    let mut lines = Vec::new();
+
+    let note_args = request.get_note_args();
+    lines.push(format!("input notes: {}", request.input_notes().len()));
+    for note in request.input_notes() {
+        let args = match note_args.get(&note.id()) {
+            Some(args) => format!(" (args {})", format_word(args)),
+            None => String::new(),
+        };
+        lines.push(format!("  {}{}", note.id().to_hex(), args));
+    }
+
+    let own_notes = request.expected_output_own_notes();
+    lines.push(format!("output notes: {}", own_notes.len()));
+    for note in &own_notes {
+        lines.push(format!(
+            "  {} ({} assets)",
+            note.id().to_hex(),
+            note.assets().num_assets()
+        ));
+    }
+
+    let script = match request.script_template() {
+        Some(TransactionScriptTemplate::CustomScript(script)) => {
+            format!("custom ({})", script.root().to_hex())
+        }
+        Some(TransactionScriptTemplate::SendNotes(notes)) => {
+            format!("send {} notes", notes.len())
+        }
+        None => "none".to_string(),
+    };
+    lines.push(format!("script: {}", script));
+    lines.push(format!(
+        "advice map entries: {}",
+        request.advice_map().len()
+    ));
+    lines.push(format!(
+        "foreign accounts: {}",
+        request.foreign_accounts().len()
+    ));
+
+    lines.join("\n")
+}
+
+// Where transactions are proven: locally, or by a remote proving service
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Prover {
+    #[default]
+    Local,
+    Remote(String),
+}
+
+impl Prover {
+    pub fn build(&self) -> Arc<dyn TransactionProver> {
+        match self {
+            Prover::Local => Arc::new(LocalTransactionProver::default()),
+            Prover::Remote(url) => Arc::new(RemoteTransactionProver::new(url.clone())),
+        }
+    }
+}
+
+// Proves, submits & applies an already executed transaction with the client's prover
+async fn prove_and_submit(
+    client: &mut Client,
+    tx_result: &TransactionResult,
+) -> Result<TransactionId, ClientError> {
+    let prover = client.prover();
+    prove_and_submit_with(client, tx_result, prover).await
+}
+
+async fn prove_and_submit_with(
+    client: &mut Client,
+    tx_result: &TransactionResult,
+    prover: Arc<dyn TransactionProver>,
+) -> Result<TransactionId, ClientError> {
+    let tx_id = tx_result.executed_transaction().id();
+
+    let proven_tx = client.prove_transaction_with(tx_result, prover).await?;
+    let submission_height = client
+        .submit_proven_transaction(proven_tx, tx_result)
+        .await?;
+    client
+        .apply_transaction(tx_result, submission_height)
+        .await?;
+
+    Ok(tx_id)
+}
+
+// What a transaction should do, for `build_intent` to turn into a request
+#[derive(Debug, Clone)]
+pub enum Intent {
+    // Run the script at `script_path` against the counter contract's library
+    Increment { script_path: PathBuf },
+    // Send `assets` to `to` in a public P2ID note
+    Transfer { to: AccountId, assets: Vec<Asset> },
+    // Mint `amount` of the executing faucet's token to `to` in a public P2ID note
+    Mint { to: AccountId, amount: u64 },
+    // Consume notes already in the store
+    Consume(Vec<NoteId>),
+}
+
+// Builds the request carrying out `intent` on behalf of `account`
+pub async fn build_intent(
+    client: &mut Client,
+    account: &Account,
+    intent: Intent,
+) -> Result<TransactionRequest, Box<dyn std::error::Error>> {
+    let request = match intent {
+        Intent::Increment { script_path } => {
+            let account_code = fs::read_to_string(Path::new("./masm/accounts/counter.masm"))?;
+            let script_code = fs::read_to_string(&script_path)?;
+            let library = create_library(account_code, COUNTER_LIBRARY_PATH, true)?;
+            let script = create_tx_script(script_code, Some(library))?;
+            TransactionRequestBuilder::new()
+                .custom_script(script)
+                .build()?
+        }
+        Intent::Transfer { to, assets } => {
+            let payment = PaymentNoteDescription::new(assets, account.id(), to);
+            TransactionRequestBuilder::new().build_pay_to_id(
+                payment,
+                NoteType::Public,
+                client.rng(),
+            )?
+        }
+        Intent::Mint { to, amount } => {
+            check_mint_supply(account, amount)?;
+            let asset = FungibleAsset::new(account.id(), amount)?;
+            TransactionRequestBuilder::new().build_mint_fungible_asset(
+                asset,
+                to,
+                NoteType::Public,
+                client.rng(),
+            )?
+        }
+        Intent::Consume(note_ids) => {
+            let notes = get_notes_by_id(client, &note_ids).await?;
+            TransactionRequestBuilder::new().build_consume_notes(notes)?
+        }
+    };
+
+    Ok(request)
+}
+
+// Executes, proves & submits a transaction, then waits for it to be committed
+pub async fn submit_and_wait(
+    client: &mut Client,
+    account_id: AccountId,
+    request: TransactionRequest,
+) -> Result<TransactionId, ClientError> {
+    let prover = client.prover();
+    let tx_result = submit_and_wait_with(client, account_id, request, prover).await?;
+    Ok(tx_result.id())
+}
+
+// Like `submit_and_wait`, then brings every account in `also_sync` up to date
+pub async fn submit_and_sync_accounts(
+    client: &mut Client,
+    account_id: AccountId,
+    request: TransactionRequest,
+    also_sync: &[AccountId],
+) -> Result<TransactionId, ClientError> {
+    let tx_id = submit_and_wait(client, account_id, request).await?;
+
+    client.sync_state().await?;
+    for &id in also_sync {
+        if client.get_account(id).await?.is_none() {
+            client.import_account_by_id(id).await?;
+        }
+    }
+
+    Ok(tx_id)
+}
+
+// Like `submit_and_wait`, but proves with `prover` and stops waiting once `shutdown` is set
+pub async fn submit_with_prover(
+    client: &mut Client,
+    account_id: AccountId,
+    request: TransactionRequest,
+    prover: &Prover,
+    shutdown: Option<watch::Receiver<bool>>,
+) -> Result<TransactionId, WaitError> {
+    let tx_result = execute_and_submit(client, account_id, request, prover.build()).await?;
+    wait_for_tx_cancellable(client, tx_result.id(), shutdown).await?;
+    Ok(tx_result.id())
+}
+
+// Like `submit_and_wait`, but also returns the notes the transaction created
+pub async fn submit_and_collect_notes(
+    client: &mut Client,
+    account_id: AccountId,
+    request: TransactionRequest,
+) -> Result<(TransactionId, Vec<Note>), ClientError> {
+    let prover = client.prover();
+    let tx_result = submit_and_wait_with(client, account_id, request, prover).await?;
+
+    let notes = tx_result
+        .created_notes()
+        .iter()
+        .filter_map(|note| match note {
+            OutputNote::Full(note) => Some(note.clone()),
+            OutputNote::Partial(_) | OutputNote::Header(_) => None,
+        })
+        .collect();
+
+    Ok((tx_result.id(), notes))
+}
+
+// Like `submit_and_collect_notes`, for a script emitting `recipients` among `count` notes
+pub async fn submit_expecting_notes(
+    client: &mut Client,
+    account_id: AccountId,
+    builder: TransactionRequestBuilder,
+    recipients: Vec<NoteRecipient>,
+    count: usize,
+) -> Result<(TransactionId, Vec<Note>), Box<dyn std::error::Error>> {
+    let request = builder.expected_output_recipients(recipients).build()?;
+
+    log_request(&request);
+    let tx_result = client.execute_transaction(account_id, request).await?;
+    let created = tx_result.created_notes().num_notes();
+    if created != count {
+        return Err(CommonError::UnexpectedOutputNotes {
+            expected: count,
+            created,
+        }
+        .into());
+    }
+
+    let tx_id = prove_and_submit(client, &tx_result).await?;
+    wait_for_tx(client, tx_id).await?;
+
+    let notes = tx_result
+        .created_notes()
+        .iter()
+        .filter_map(|note| match note {
+            OutputNote::Full(note) => Some(note.clone()),
+            OutputNote::Partial(_) | OutputNote::Header(_) => None,
+        })
+        .collect();
+
+    Ok((tx_id, notes))
+}
+
+// How long each phase of one transaction took, from `submit_timed`
+#[derive(Debug, Clone, Copy)]
+pub struct TxTiming {
+    pub tx_id: TransactionId,
+    pub execute: Duration,
+    pub prove: Duration,
+    // Submitting the proof & applying the transaction to the local store
+    pub submit: Duration,
+    // From submission until the transaction was committed in a block
+    pub commit: Duration,
+}
+
+impl TxTiming {
+    pub fn total(&self) -> Duration {
+        self.execute + self.prove + self.submit + self.commit
+    }
+}
+
+// Like `submit_and_wait`, timing execution, proving, submission & commitment separately
+pub async fn submit_timed(
+    client: &mut Client,
+    account_id: AccountId,
+    request: TransactionRequest,
+) -> Result<TxTiming, ClientError> {
+    let started = Instant::now();
+    let tx_result = client.execute_transaction(account_id, request).await?;
+    let execute = started.elapsed();
+
+    let started = Instant::now();
+    let prover = client.prover();
+    let proven_tx = client.prove_transaction_with(&tx_result, prover).await?;
+    let prove = started.elapsed();
+
+    let started = Instant::now();
+    let submission_height = client
+        .submit_proven_transaction(proven_tx, &tx_result)
+        .await?;
+    client
+        .apply_transaction(&tx_result, submission_height)
+        .await?;
+    let submit = started.elapsed();
+
+    let tx_id = tx_result.executed_transaction().id();
+    let started = Instant::now();
+    wait_for_tx(client, tx_id).await?;
+    let commit = started.elapsed();
+
+    Ok(TxTiming {
+        tx_id,
+        execute,
+        prove,
+        submit,
+        commit,
+    })
+}
+
+// Result of `bench_increment`: wall time for the whole run plus each transaction's timing
+#[derive(Debug, Clone)]
+pub struct BenchReport {
+    pub total: Duration,
+    pub timings: Vec<TxTiming>,
+}
+
+impl BenchReport {
+    // Committed transactions per second over the whole run
+    pub fn tps(&self) -> f64 {
+        self.timings.len() as f64 / self.total.as_secs_f64()
+    }
+
+    // Mean time per transaction spent in the phase `phase` picks out, e.g. `|t| t.prove`
+    pub fn mean(&self, phase: impl Fn(&TxTiming) -> Duration) -> Duration {
+        if self.timings.is_empty() {
+            return Duration::ZERO;
+        }
+        self.timings.iter().map(phase).sum::<Duration>() / self.timings.len() as u32
+    }
+}
+
+impl fmt::Display for BenchReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} transactions in {:.2?} ({:.3} tx/s)",
+            self.timings.len(),
+            self.total,
+            self.tps()
+        )?;
+        write!(
+            f,
+            "mean latency {:.2?}: execute {:.2?}, prove {:.2?}, submit {:.2?}, commit {:.2?}",
+            self.mean(TxTiming::total),
+            self.mean(|t| t.execute),
+            self.mean(|t| t.prove),
+            self.mean(|t| t.submit),
+            self.mean(|t| t.commit)
+        )
+    }
+}
+
+// Increments the counter `account_id` `count` times and reports the throughput
+pub async fn bench_increment(
+    client: &mut Client,
+    account_id: AccountId,
+    count: usize,
+) -> Result<BenchReport, Box<dyn std::error::Error>> {
+    let counter_code = fs::read_to_string("./masm/accounts/counter.masm")?;
+    let script_code = fs::read_to_string("./masm/scripts/increment_script.masm")?;
+    let library = create_library(counter_code, COUNTER_LIBRARY_PATH, true)?;
+    let tx_script = create_tx_script(script_code, Some(library))?;
+
+    let started = Instant::now();
+    let mut timings = Vec::with_capacity(count);
+    for _ in 0..count {
+        let request = TransactionRequestBuilder::new()
+            .custom_script(tx_script.clone())
+            .build()?;
+        timings.push(submit_timed(client, account_id, request).await?);
+    }
+
+    Ok(BenchReport {
+        total: started.elapsed(),
+        timings,
+    })
+}
+
+async fn submit_and_wait_with(
+    client: &mut Client,
+    account_id: AccountId,
+    request: TransactionRequest,
+    prover: Arc<dyn TransactionProver>,
+) -> Result<TransactionResult, ClientError> {
+    let tx_result = execute_and_submit(client, account_id, request, prover).await?;
+    wait_for_tx(client, tx_result.id()).await?;
+
+    Ok(tx_result)
+}
+
+// Executes, proves with `prover` & submits a transaction, without waiting for it
+async fn execute_and_submit(
+    client: &mut Client,
+    account_id: AccountId,
+    request: TransactionRequest,
+    prover: Arc<dyn TransactionProver>,
+) -> Result<TransactionResult, ClientError> {
+    log_request(&request);
+    let tx_result = client.execute_transaction(account_id, request).await?;
+    eprintln!(
+        "transaction fee: {}",
+        FeeEstimate::from(tx_result.executed_transaction())
+    );
+    prove_and_submit_with(client, &tx_result, prover).await?;
+
+    Ok(tx_result)
+}
+
+// Signs with `authenticator` through a second client (and store connection) over `config`
+pub async fn submit_with_authenticator<A>(
+    client: &mut Client,
+    account_id: AccountId,
+    request: TransactionRequest,
+    authenticator: Arc<A>,
+    config: &ClientConfig,
+) -> Result<TransactionId, Box<dyn std::error::Error>>
+where
+    A: BuilderAuthenticator + Send + Sync,
+{
+    let mut signer = ClientBuilder::<A>::new()
+        .rpc(build_rpc(&config.endpoint, &config.rpc))
+        .sqlite_store(config.store.sqlite_path())
+        .authenticator(authenticator)
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    log_request(&request);
+    let tx_result = signer.execute_transaction(account_id, request).await?;
+    let proven_tx = signer.prove_transaction(&tx_result).await?;
+    drop(signer);
+
+    let tx_id = tx_result.executed_transaction().id();
+    let executed_from = tx_result
+        .executed_transaction()
+        .initial_account()
+        .initial_commitment();
+    let current: Account = client.try_get_account(account_id).await?.try_into()?;
+    if current.commitment() != executed_from {
+        return Err(CommonError::ForeignStore {
+            tx_id,
+            path: config.store.sqlite_path(),
+        }
+        .into());
+    }
+
+    let submission_height = client
+        .submit_proven_transaction(proven_tx, &tx_result)
+        .await?;
+    client
+        .apply_transaction(&tx_result, submission_height)
+        .await?;
+    wait_for_tx(client, tx_id).await?;
+
+    Ok(tx_id)
+}
+
+// Submits `script` against each account without waiting, reporting every failure
+pub async fn submit_script_to_all(
+    client: &mut Client,
+    account_ids: &[AccountId],
+    script: TransactionScript,
+) -> Result<Vec<TransactionId>, CommonError> {
+    let mut submitted = Vec::with_capacity(account_ids.len());
+    let mut failed = Vec::new();
+
+    for account_id in account_ids {
+        let result = match TransactionRequestBuilder::new()
+            .custom_script(script.clone())
+            .build()
+        {
+            Ok(request) => client
+                .submit_new_transaction(*account_id, request)
+                .await
+                .map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+
+        match result {
+            Ok(tx_id) => submitted.push(tx_id),
+            Err(reason) => failed.push((*account_id, reason)),
+        }
+    }
+
+    if failed.is_empty() {
+        Ok(submitted)
+    } else {
+        Err(CommonError::PartialSubmission { submitted, failed })
+    }
+}
+
+// Submits a transaction that only bumps the account's nonce and waits for it
+pub async fn submit_noop(
+    client: &mut Client,
+    account_id: AccountId,
+) -> Result<TransactionId, Box<dyn std::error::Error>> {
+    let script = create_tx_script("begin\n    nop\nend".to_string(), None)?;
+    let request = TransactionRequestBuilder::new()
+        .custom_script(script)
+        .build()?;
+
+    Ok(submit_and_wait(client, account_id, request).await?)
+}
+
+// Like `submit_and_wait`, rebuilding the transaction up to `max_retries` times on stale state
+pub async fn submit_with_nonce_retry(
+    client: &mut Client,
+    account_id: AccountId,
+    request: TransactionRequest,
+    max_retries: usize,
+) -> Result<TransactionId, ClientError> {
+    let mut attempt = 0;
+
+    loop {
+        match client
+            .submit_new_transaction(account_id, request.clone())
+            .await
+        {
+            Ok(tx_id) => {
+                wait_for_tx(client, tx_id).await?;
+                return Ok(tx_id);
+            }
+            Err(e) if attempt < max_retries && is_stale_account_error(&e) => {
+                attempt += 1;
+                eprintln!(
+                    "stale account state ({}), re-syncing & retrying ({}/{})",
+                    e, attempt, max_retries
+                );
+                // The request is re-executed against the account state this sync brings in
+                sync_state_retry(client, 3).await?;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+// Whether the node rejected a submitted transaction because it was built on stale account state
+pub fn is_stale_account_error(err: &ClientError) -> bool {
+    match err {
+        ClientError::RpcError(
+            rpc_error @ RpcError::GrpcError {
+                endpoint: NodeRpcClientEndpoint::SubmitProvenTx,
+                error_kind: GrpcError::InvalidArgument | GrpcError::Unknown(_),
+                ..
+            },
+        ) => error_chain_mentions(rpc_error, &["commitment", "nonce"]),
+        _ => false,
+    }
+}
+
+// Like `submit_and_wait`, with extra advice map entries available to the transaction
+pub async fn submit_with_advice(
+    client: &mut Client,
+    account_id: AccountId,
+    builder: TransactionRequestBuilder,
+    advice: Vec<(Word, Vec<Felt>)>,
+) -> Result<TransactionId, Box<dyn std::error::Error>> {
+    let request = builder.extend_advice_map(advice).build()?;
+
+    Ok(submit_and_wait(client, account_id, request).await?)
+}
+
+// Like `submit_and_wait`, but the transaction is only valid for `expiration_blocks` blocks
+pub async fn submit_with_expiration(
+    client: &mut Client,
+    account_id: AccountId,
+    builder: TransactionRequestBuilder,
+    expiration_blocks: Option<u16>,
+) -> Result<TransactionId, Box<dyn std::error::Error>> {
+    let builder = match expiration_blocks {
+        Some(delta) => builder.expiration_delta(delta),
+        None => builder,
+    };
+    let request = builder.build()?;
+
+    let tx_result = client.execute_transaction(account_id, request).await?;
+    let expiration_block = tx_result.executed_transaction().expiration_block_num();
+    let tx_id = prove_and_submit(client, &tx_result).await?;
+
+    wait_for_tx_or_expiry(client, tx_id, expiration_block).await?;
+
+    Ok(tx_id)
+}
+
+// Resubmits a fresh request from `request_factory` each time one expires, up to `max_attempts`
+pub async fn submit_reliably(
+    client: &mut Client,
+    account_id: AccountId,
+    request_factory: impl Fn() -> TransactionRequestBuilder,
+    expiration_blocks: u16,
+    max_attempts: usize,
+) -> Result<TransactionId, Box<dyn std::error::Error>> {
+    let mut attempt = 1;
+    loop {
+        let result = submit_with_expiration(
+            client,
+            account_id,
+            request_factory(),
+            Some(expiration_blocks),
+        )
+        .await;
+
+        match result {
+            Err(e)
+                if attempt < max_attempts
+                    && matches!(
+                        e.downcast_ref::<CommonError>(),
+                        Some(CommonError::TransactionExpired { .. })
+                    ) =>
+            {
+                eprintln!("{}, resubmitting ({}/{})", e, attempt, max_attempts);
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+// Waits for a transaction to be committed, erroring if it expires or is discarded first
+pub async fn wait_for_tx_or_expiry(
+    client: &mut Client,
+    tx_id: TransactionId,
+    expiration_block: BlockNumber,
+) -> Result<(), Box<dyn std::error::Error>> {
+    loop {
+        let summary = sync_state_retry(client, 3).await?;
+
+        let txs = client
+            .get_transactions(TransactionFilter::Ids(vec![tx_id]))
+            .await?;
+
+        match txs.first().map(|tx| &tx.status) {
+            Some(TransactionStatus::Committed { .. }) => {
+                eprintln!("✅ Transaction {} committed", tx_id.to_hex());
+                return Ok(());
+            }
+            Some(TransactionStatus::Discarded(DiscardCause::Expired)) => {
+                return Err(CommonError::TransactionExpired {
+                    tx_id,
+                    expiration_block,
+                }
+                .into());
+            }
+            Some(TransactionStatus::Discarded(cause)) => {
+                return Err(CommonError::TransactionDiscarded {
+                    tx_id,
+                    cause: format!("{:?}", cause),
+                }
+                .into());
+            }
+            _ => {}
+        }
+
+        if summary.block_num > expiration_block {
+            return Err(CommonError::TransactionExpired {
+                tx_id,
+                expiration_block,
+            }
+            .into());
+        }
+
+        eprintln!(
+            "Transaction {} not yet committed (expires at block {}). Waiting...",
+            tx_id.to_hex(),
+            expiration_block
+        );
+        sleep(Duration::from_secs(2)).await;
+    }
+}
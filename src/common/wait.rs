@@ -0,0 +1,472 @@
+// Waiting on transactions, notes & account state, optionally until a shutdown signal
+
+use miden_client::{
+    ClientError,
+    account::{Account, AccountId, StorageSlotName},
+    block::BlockNumber,
+    note::{Note, NoteId},
+    store::{AccountStatus, InputNoteRecord, NoteFilter, TransactionFilter},
+    transaction::{TransactionId, TransactionStatus},
+};
+use std::{collections::BTreeSet, fmt};
+use tokio::{
+    sync::watch,
+    time::{Duration, Instant, sleep},
+};
+
+use super::{Client, CommonError, client::sync_state_retry};
+
+// Error from a wait helper that can be interrupted by a shutdown signal
+#[derive(Debug)]
+pub enum WaitError {
+    Client(ClientError),
+    Cancelled,
+}
+
+impl fmt::Display for WaitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WaitError::Client(e) => write!(f, "{}", e),
+            WaitError::Cancelled => write!(f, "wait cancelled by shutdown signal"),
+        }
+    }
+}
+
+impl std::error::Error for WaitError {}
+
+impl From<ClientError> for WaitError {
+    fn from(e: ClientError) -> Self {
+        WaitError::Client(e)
+    }
+}
+
+// Unwraps the client error of a wait that was given no shutdown signal
+fn uncancelled(e: WaitError) -> ClientError {
+    match e {
+        WaitError::Client(e) => e,
+        WaitError::Cancelled => unreachable!("wait without a shutdown signal was cancelled"),
+    }
+}
+
+// Returns a receiver that flips to true on Ctrl-C, for passing to the cancellable waits
+pub fn shutdown_on_ctrl_c() -> watch::Receiver<bool> {
+    let (sender, receiver) = watch::channel(false);
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            let _ = sender.send(true);
+        }
+    });
+    receiver
+}
+
+fn is_cancelled(shutdown: &Option<watch::Receiver<bool>>) -> bool {
+    shutdown.as_ref().is_some_and(|rx| *rx.borrow())
+}
+
+// Sleeps between polls, returning early with `Cancelled` once shutdown is signalled
+async fn poll_delay(shutdown: &mut Option<watch::Receiver<bool>>) -> Result<(), WaitError> {
+    let delay = Duration::from_secs(2);
+    match shutdown {
+        Some(rx) => {
+            tokio::select! {
+                _ = sleep(delay) => {}
+                // A dropped sender can never signal, so keep the normal delay
+                signalled = rx.wait_for(|stop| *stop) => {
+                    if signalled.is_err() {
+                        sleep(delay).await;
+                    }
+                }
+            }
+        }
+        None => sleep(delay).await,
+    }
+
+    if is_cancelled(shutdown) {
+        return Err(WaitError::Cancelled);
+    }
+    Ok(())
+}
+
+// Waits for transaction to be committed
+pub async fn wait_for_tx(client: &mut Client, tx_id: TransactionId) -> Result<(), ClientError> {
+    wait_for_tx_cancellable(client, tx_id, None)
+        .await
+        .map_err(uncancelled)
+}
+
+// Like `wait_for_tx`, but stops between syncs once `shutdown` is set
+pub async fn wait_for_tx_cancellable(
+    client: &mut Client,
+    tx_id: TransactionId,
+    mut shutdown: Option<watch::Receiver<bool>>,
+) -> Result<(), WaitError> {
+    loop {
+        if is_cancelled(&shutdown) {
+            return Err(WaitError::Cancelled);
+        }
+        sync_state_retry(client, 3).await?;
+
+        let txs = client
+            .get_transactions(TransactionFilter::Ids(vec![tx_id]))
+            .await?;
+
+        let committed = txs
+            .first()
+            .is_some_and(|tx| matches!(tx.status, TransactionStatus::Committed { .. }));
+
+        if committed {
+            eprintln!("✅ Transaction {} committed", tx_id.to_hex());
+            return Ok(());
+        }
+
+        eprintln!(
+            "Transaction {} not yet committed. Waiting...",
+            tx_id.to_hex()
+        );
+        poll_delay(&mut shutdown).await?;
+    }
+}
+
+// Waits for note
+pub async fn wait_for_note(
+    client: &mut Client,
+    account_id: Option<AccountId>,
+    expected: &Note,
+) -> Result<(), ClientError> {
+    wait_for_note_cancellable(client, account_id, expected, None)
+        .await
+        .map_err(uncancelled)
+}
+
+// Like `wait_for_note`, but stops between syncs once `shutdown` is set
+pub async fn wait_for_note_cancellable(
+    client: &mut Client,
+    account_id: Option<AccountId>,
+    expected: &Note,
+    mut shutdown: Option<watch::Receiver<bool>>,
+) -> Result<(), WaitError> {
+    loop {
+        if is_cancelled(&shutdown) {
+            return Err(WaitError::Cancelled);
+        }
+        sync_state_retry(client, 3).await?;
+
+        if find_note(client, account_id, expected.id())
+            .await?
+            .is_some()
+        {
+            eprintln!("✅ note found {}", expected.id().to_hex());
+            return Ok(());
+        }
+        // A network note can be consumed between two syncs; report it as `AlreadyConsumed`
+        if let Some(record) = client.get_input_note(expected.id()).await?
+            && record.is_consumed()
+        {
+            eprintln!("note {} already consumed", expected.id().to_hex());
+            return Ok(());
+        }
+
+        eprintln!("Note {} not found. Waiting...", expected.id().to_hex());
+        poll_delay(&mut shutdown).await?;
+    }
+}
+
+// Like `wait_for_note`, but returns the note's record, or times out after `max_wait`
+pub async fn wait_for_note_record(
+    client: &mut Client,
+    account_id: Option<AccountId>,
+    note_id: NoteId,
+    max_wait: Duration,
+) -> Result<InputNoteRecord, Box<dyn std::error::Error>> {
+    let started = Instant::now();
+
+    loop {
+        sync_state_retry(client, 3).await?;
+
+        if let Some(record) = find_note(client, account_id, note_id).await? {
+            eprintln!("✅ note found {}", note_id.to_hex());
+            return Ok(record);
+        }
+
+        if started.elapsed() >= max_wait {
+            return Err(CommonError::NoteWaitTimeout {
+                note_id,
+                waited: max_wait,
+            }
+            .into());
+        }
+
+        eprintln!("Note {} not found. Waiting...", note_id.to_hex());
+        sleep(Duration::from_secs(2)).await;
+    }
+}
+
+// Looks for a note among those consumable right now and those committed
+async fn find_note(
+    client: &mut Client,
+    account_id: Option<AccountId>,
+    note_id: NoteId,
+) -> Result<Option<InputNoteRecord>, ClientError> {
+    let consumable = client.get_consumable_notes(account_id).await?;
+    if let Some((record, _)) = consumable.into_iter().find(|(rec, _)| rec.id() == note_id) {
+        return Ok(Some(record));
+    }
+
+    let committed = client.get_input_notes(NoteFilter::Committed).await?;
+    Ok(committed.into_iter().find(|rec| rec.id() == note_id))
+}
+
+// Calls `on_note` for every note that becomes consumable by the account, until `shutdown`
+pub async fn watch_for_notes(
+    client: &mut Client,
+    account_id: AccountId,
+    mut shutdown: watch::Receiver<bool>,
+    mut on_note: impl FnMut(InputNoteRecord),
+) -> Result<(), ClientError> {
+    let mut seen = BTreeSet::new();
+
+    while !*shutdown.borrow() {
+        sync_state_retry(client, 3).await?;
+
+        for (record, _) in client.get_consumable_notes(Some(account_id)).await? {
+            if seen.insert(record.id()) {
+                on_note(record);
+            }
+        }
+
+        tokio::select! {
+            _ = sleep(Duration::from_secs(2)) => {}
+            changed = shutdown.changed() => {
+                if changed.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Waits until a felt of an account's storage slot equals the expected value
+pub async fn wait_for_storage_value(
+    client: &mut Client,
+    account_id: AccountId,
+    slot_name: &str,
+    index: usize,
+    expected: u64,
+    max_wait: Duration,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let storage_slot_name = StorageSlotName::new(slot_name)?;
+    let started = Instant::now();
+    let mut last_observed = None;
+
+    loop {
+        if client.get_account(account_id).await?.is_none() {
+            // The node may not know a freshly deployed account yet
+            if let Err(e) = client.import_account_by_id(account_id).await {
+                eprintln!("Account {} not yet available: {}", account_id.to_hex(), e);
+            }
+        } else {
+            sync_state_retry(client, 3).await?;
+        }
+
+        if let Some(record) = client.get_account(account_id).await? {
+            let account: Account = record.try_into()?;
+            let word = account.storage().get_item(&storage_slot_name)?;
+            last_observed = word.get(index).map(|felt| felt.as_int());
+
+            if last_observed == Some(expected) {
+                eprintln!("✅ {} reached {}", slot_name, expected);
+                return Ok(());
+            }
+        }
+
+        if started.elapsed() >= max_wait {
+            return Err(CommonError::StorageWaitTimeout {
+                account_id,
+                slot_name: slot_name.to_string(),
+                expected,
+                last_observed,
+            }
+            .into());
+        }
+
+        eprintln!(
+            "{} is {:?}, waiting for {}...",
+            slot_name, last_observed, expected
+        );
+        sleep(Duration::from_secs(2)).await;
+    }
+}
+
+// Waits until the node knows the account, i.e. a transaction against it was committed
+pub async fn wait_for_account_commit(
+    client: &mut Client,
+    account_id: AccountId,
+    max_wait: Duration,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let started = Instant::now();
+
+    loop {
+        sync_state_retry(client, 3).await?;
+
+        let record = client
+            .get_account(account_id)
+            .await?
+            .ok_or(ClientError::AccountDataNotFound(account_id))?;
+        if !matches!(record.status(), AccountStatus::New { .. }) {
+            eprintln!("✅ account {} committed on-chain", account_id.to_hex());
+            return Ok(());
+        }
+
+        if started.elapsed() >= max_wait {
+            return Err(CommonError::AccountCommitTimeout {
+                account_id,
+                waited: max_wait,
+            }
+            .into());
+        }
+
+        eprintln!(
+            "account {} only known locally, waiting for commit...",
+            account_id.to_hex()
+        );
+        sleep(Duration::from_secs(2)).await;
+    }
+}
+
+// Imports every untracked account in `ids`, then syncs once and returns their state in order
+pub async fn import_accounts_by_id(
+    client: &mut Client,
+    ids: &[AccountId],
+) -> Result<Vec<Account>, Box<dyn std::error::Error>> {
+    let mut imported = Vec::with_capacity(ids.len());
+    let mut failed = Vec::new();
+
+    for &account_id in ids {
+        let result = match client.get_account(account_id).await {
+            Ok(Some(_)) => Ok(()),
+            Ok(None) => client.import_account_by_id(account_id).await,
+            Err(e) => Err(e),
+        };
+
+        match result {
+            Ok(()) => imported.push(account_id),
+            Err(e) => failed.push((account_id, e.to_string())),
+        }
+    }
+
+    if !failed.is_empty() {
+        return Err(CommonError::PartialImport { imported, failed }.into());
+    }
+
+    sync_state_retry(client, 3).await?;
+
+    let mut accounts = Vec::with_capacity(ids.len());
+    for &account_id in ids {
+        accounts.push(client.try_get_account(account_id).await?.try_into()?);
+    }
+
+    Ok(accounts)
+}
+
+// Imports a public account by id, retrying until the node has indexed it
+pub async fn import_account_by_id_waiting(
+    client: &mut Client,
+    account_id: AccountId,
+    max_wait: Duration,
+) -> Result<Account, Box<dyn std::error::Error>> {
+    let started = Instant::now();
+
+    loop {
+        if client.get_account(account_id).await?.is_none()
+            && let Err(e) = client.import_account_by_id(account_id).await
+        {
+            eprintln!("account {} not importable yet: {}", account_id.to_hex(), e);
+        }
+        if let Some(record) = client.get_account(account_id).await? {
+            return Ok(record.try_into()?);
+        }
+
+        if started.elapsed() >= max_wait {
+            return Err(CommonError::AccountImportTimeout {
+                account_id,
+                waited: max_wait,
+            }
+            .into());
+        }
+
+        sleep(Duration::from_secs(2)).await;
+        sync_state_retry(client, 3).await?;
+    }
+}
+
+// Waits until the chain is `n` blocks past the current tip, for at most `max_wait`
+pub async fn wait_blocks(
+    client: &mut Client,
+    n: u32,
+    max_wait: Duration,
+) -> Result<BlockNumber, Box<dyn std::error::Error>> {
+    let mut tip = sync_state_retry(client, 3).await?.block_num;
+    let target = tip + n;
+    let started = Instant::now();
+
+    while tip < target {
+        if started.elapsed() >= max_wait {
+            return Err(CommonError::BlockWaitTimeout {
+                target,
+                reached: tip,
+                waited: max_wait,
+            }
+            .into());
+        }
+        sleep(Duration::from_secs(1)).await;
+        tip = sync_state_retry(client, 3).await?.block_num;
+    }
+
+    Ok(tip)
+}
+
+// Waits until the account's nonce is at least `min_nonce`, returning the nonce observed
+pub async fn wait_for_nonce(
+    client: &mut Client,
+    account_id: AccountId,
+    min_nonce: u64,
+    max_wait: Duration,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    let started = Instant::now();
+    let mut last_observed = None;
+
+    loop {
+        if client.get_account(account_id).await?.is_none() {
+            if let Err(e) = client.import_account_by_id(account_id).await {
+                eprintln!("Account {} not yet available: {}", account_id.to_hex(), e);
+            }
+        } else {
+            sync_state_retry(client, 3).await?;
+        }
+
+        if let Some(record) = client.get_account(account_id).await? {
+            let account: Account = record.try_into()?;
+            let nonce = account.nonce().as_int();
+            last_observed = Some(nonce);
+
+            if nonce >= min_nonce {
+                eprintln!("✅ nonce of {} reached {}", account_id.to_hex(), nonce);
+                return Ok(nonce);
+            }
+        }
+
+        if started.elapsed() >= max_wait {
+            return Err(CommonError::NonceWaitTimeout {
+                account_id,
+                min_nonce,
+                last_observed,
+            }
+            .into());
+        }
+
+        eprintln!("nonce is {:?}, waiting for {}...", last_observed, min_nonce);
+        sleep(Duration::from_secs(2)).await;
+    }
+}
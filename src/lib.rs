@@ -1 +1,4 @@
 pub mod common;
+pub mod prelude;
+
+pub use common::*;
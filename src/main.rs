@@ -1,44 +1,69 @@
 use std::{fs, path::Path};
 
-use masm_project_template::common::{
-    create_library, create_public_immutable_contract, create_tx_script, delete_keystore_and_store,
-    instantiate_client, wait_for_tx,
+use clap::Parser;
+use masm_project_template::{
+    common::{
+        COUNTER_SLOT_NAME, chain_head, check_endpoint, counter_library_path, create_library,
+        create_public_immutable_contract, create_tx_script, delete_keystore_and_store,
+        diff_snapshots, emit_json, execute_local, format_account_id, instantiate_client,
+        print_account_delta, shutdown_on_ctrl_c, snapshot_account, wait_for_tx_cancellable,
+        word_to_u64, write_deployment_manifest,
+    },
+    prelude::*,
 };
 
-use miden_client::{
-    Word,
-    account::{Account, StorageSlotName},
-    rpc::Endpoint,
-    transaction::TransactionRequestBuilder,
-};
-use miden_protocol::address::NetworkId;
+/// Deploys the counter contract on testnet and increments it once
+#[derive(Parser)]
+struct Args {
+    /// Print the results as one JSON object instead
+    #[arg(long)]
+    json: bool,
+}
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let json = Args::parse().json;
+
     delete_keystore_and_store().await;
 
     // -------------------------------------------------------------------------
     // Instantiate client
     // -------------------------------------------------------------------------
     let endpoint = Endpoint::testnet();
-    let mut client = instantiate_client(endpoint).await.unwrap();
+    check_endpoint(&endpoint, 10_000).await?;
+    let mut client = instantiate_client(endpoint, StoreBackend::default(), &RpcConfig::default())
+        .await
+        .unwrap();
 
-    let sync_summary = client.sync_state().await.unwrap();
-    println!("⛓  Latest block: {}", sync_summary.block_num);
+    let head = chain_head(&mut client).await?;
+    if !json {
+        println!("⛓  Latest block: {}", head);
+    }
 
     // -------------------------------------------------------------------------
     // STEP 1 – Deploy the counter contract
     // -------------------------------------------------------------------------
     let counter_code = fs::read_to_string(Path::new("./masm/accounts/counter.masm")).unwrap();
 
-    let counter_contract = create_public_immutable_contract(&mut client, &counter_code).await?;
+    let counter_contract =
+        create_public_immutable_contract(&mut client, &counter_code, true).await?;
 
     client.add_account(&counter_contract, false).await.unwrap();
-
-    println!(
-        "📄 Counter contract ID: {}",
-        counter_contract.id().to_bech32(NetworkId::Testnet)
-    );
+    let before = snapshot_account(&counter_contract);
+
+    if !json {
+        println!(
+            "📄 Counter contract ID: {}",
+            format_account_id(counter_contract.id(), Network::Testnet)
+        );
+    }
+    write_deployment_manifest(
+        Path::new("./counter_deployment.json"),
+        counter_contract.id(),
+        counter_library_path(),
+        Network::Testnet,
+        &counter_code,
+    )?;
 
     // -------------------------------------------------------------------------
     // STEP 2 – Compile the increment script
@@ -46,9 +71,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let script_code =
         fs::read_to_string(Path::new("./masm/scripts/increment_script.masm")).unwrap();
 
-    let library_path = "external_contract::counter_contract";
+    let library_path = counter_library_path();
 
-    let library = create_library(counter_code, library_path).unwrap();
+    let library = create_library(counter_code, library_path, true).unwrap();
 
     let tx_script = create_tx_script(script_code, Some(library)).unwrap();
 
@@ -60,13 +85,26 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .build()
         .unwrap();
 
+    // Dry run locally to preview the storage changes before submitting
+    let executed_tx = execute_local(
+        &mut client,
+        counter_contract.id(),
+        tx_increment_request.clone(),
+    )
+    .await?;
+    if !json {
+        print_account_delta(&executed_tx);
+    }
+
     let tx_id = client
         .submit_new_transaction(counter_contract.id(), tx_increment_request)
         .await
         .unwrap();
 
-    println!("🚀 Increment transaction submitted – waiting for finality …");
-    wait_for_tx(&mut client, tx_id).await?;
+    if !json {
+        println!("🚀 Increment transaction submitted – waiting for finality …");
+    }
+    wait_for_tx_cancellable(&mut client, tx_id, Some(shutdown_on_ctrl_c())).await?;
 
     // -------------------------------------------------------------------------
     // STEP 4 – Fetch contract state & verify
@@ -76,7 +114,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     delete_keystore_and_store().await;
 
     let endpoint = Endpoint::testnet();
-    let mut client = instantiate_client(endpoint).await?;
+    let mut client =
+        instantiate_client(endpoint, StoreBackend::default(), &RpcConfig::default()).await?;
 
     client
         .import_account_by_id(counter_contract.id())
@@ -90,14 +129,28 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .try_into()
         .unwrap();
 
-    let storage_slot_name = StorageSlotName::new("counter::counter_slot")?;
+    let storage_slot_name = StorageSlotName::new(COUNTER_SLOT_NAME)?;
     let word: Word = account_record
         .storage()
         .get_item(&storage_slot_name)
         .unwrap();
 
-    let counter_val = word.get(3).unwrap().as_int();
+    let counter_val = word_to_u64(&word);
+
+    if json {
+        emit_json(serde_json::json!({
+            "counter_id": format_account_id(counter_contract.id(), Network::Testnet),
+            "counter_value": counter_val,
+            "tx_id": tx_id.to_hex(),
+        }))?;
+        return Ok(());
+    }
+
     println!("🔢 Counter value after tx: {}", counter_val);
+    print!(
+        "{}",
+        diff_snapshots(&before, &snapshot_account(&account_record))
+    );
     println!("✅ Success! The counter was incremented.");
 
     println!(
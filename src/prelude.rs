@@ -0,0 +1,45 @@
+// One import surface for the helpers' types: `use masm_project_template::prelude::*;`
+
+// miden_client::* (the field types it re-exports from miden_protocol)
+pub use miden_client::{ClientError, Felt, Word};
+
+// miden_client::account::*
+pub use miden_client::account::{
+    Account, AccountBuilder, AccountComponent, AccountId, AccountStorageMode, AccountType,
+    StorageSlot, StorageSlotName,
+};
+
+// miden_client::assembly::CodeBuilder, and miden_protocol::assembly::* for the raw assembler
+pub use miden_client::assembly::CodeBuilder;
+pub use miden_protocol::assembly::{Assembler, Library};
+
+// miden_protocol::account::AccountComponentCode (not re-exported by miden_client)
+pub use miden_protocol::account::AccountComponentCode;
+
+// miden_client::asset::* / auth::* / crypto::*
+pub use miden_client::asset::{Asset, FungibleAsset};
+pub use miden_client::auth::{AuthSchemeId, AuthSecretKey};
+pub use miden_client::crypto::{MerklePath, MerkleTree, NodeIndex};
+
+// miden_client::keystore::* / rpc::*
+pub use miden_client::keystore::FilesystemKeyStore;
+pub use miden_client::rpc::Endpoint;
+
+// miden_client::note::*
+pub use miden_client::note::{
+    Note, NoteAssets, NoteId, NoteInputs, NoteMetadata, NoteRecipient, NoteTag, NoteType,
+};
+
+// miden_client::store::* / transaction::*
+pub use miden_client::store::InputNoteRecord;
+pub use miden_client::transaction::{
+    TransactionId, TransactionRequest, TransactionRequestBuilder, TransactionScript,
+};
+
+// miden_standards::account::* (the wallet, faucet and auth components)
+pub use miden_standards::account::{
+    auth::AuthFalcon512Rpo, faucets::BasicFungibleFaucet, wallets::BasicWallet,
+};
+
+// crate::common::*, the helpers' own client alias, error & selector types
+pub use crate::common::{Client, CommonError, Network, Prover, RpcConfig, StoreBackend};
@@ -0,0 +1,167 @@
+mod common;
+
+use masm_project_template::common::{
+    CommonError, Network, account_type_of, expect_account_type, load_account_id,
+    load_deployment_manifest, parse_account_id, parse_connectable_network, predict_account_id,
+    resolve_counter_id, save_account_id, write_deployment_manifest,
+};
+use miden_client::{
+    Word,
+    account::{AccountStorageMode, AccountType, StorageSlot},
+};
+
+const TESTNET_COUNTER: &str = "mtst1azxmwd8waj5cuqq24h995zc73snfrp89";
+
+#[test]
+fn parse_account_id_checks_network_prefix() {
+    parse_account_id(TESTNET_COUNTER, Network::Testnet).unwrap();
+
+    let err = parse_account_id(TESTNET_COUNTER, Network::Localhost).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<CommonError>(),
+        Some(CommonError::NetworkMismatch { .. })
+    ));
+}
+
+#[test]
+fn networks_without_endpoint_are_rejected() {
+    assert_eq!(parse_connectable_network("devnet"), Ok(Network::Devnet));
+    assert!(parse_connectable_network("mainnet").is_err());
+}
+
+#[test]
+fn saved_account_id_round_trips() {
+    let account_id = parse_account_id(TESTNET_COUNTER, Network::Testnet).unwrap();
+    let dir = common::test_dir("account_id");
+    let path = dir.join("account_id.txt");
+
+    save_account_id(account_id, &path).unwrap();
+    assert_eq!(load_account_id(&path), Some(account_id));
+
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(load_account_id(&path), None);
+    std::fs::remove_dir(&dir).unwrap();
+}
+
+#[test]
+fn deployment_manifest_round_trips() {
+    let account_id = parse_account_id(TESTNET_COUNTER, Network::Testnet).unwrap();
+    let source = std::fs::read_to_string("./masm/accounts/counter.masm").unwrap();
+    let dir = common::test_dir("manifest");
+    let path = dir.join("manifest.json");
+
+    write_deployment_manifest(
+        &path,
+        account_id,
+        "external_contract::counter_contract",
+        Network::Testnet,
+        &source,
+    )
+    .unwrap();
+    let manifest = load_deployment_manifest(&path).unwrap();
+
+    assert_eq!(manifest.account_id, account_id);
+    assert_eq!(manifest.library_path, "external_contract::counter_contract");
+    assert_eq!(manifest.network, Network::Testnet);
+    assert!(manifest.matches_source(&source));
+    assert!(!manifest.matches_source("begin nop end"));
+
+    std::fs::write(&path, "{}").unwrap();
+    assert!(matches!(
+        load_deployment_manifest(&path)
+            .unwrap_err()
+            .downcast_ref::<CommonError>(),
+        Some(CommonError::InvalidManifest { .. })
+    ));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn network_parses_from_cli_name() {
+    assert_eq!("testnet".parse::<Network>(), Ok(Network::Testnet));
+    assert_eq!("Localhost".parse::<Network>(), Ok(Network::Localhost));
+    assert!("moonnet".parse::<Network>().is_err());
+    assert!(Network::Mainnet.endpoint().is_none());
+}
+
+#[tokio::test]
+async fn predicted_account_id_is_deterministic() -> Result<(), Box<dyn std::error::Error>> {
+    let code = std::fs::read_to_string("./masm/accounts/counter.masm")?;
+    let slots = || -> Result<Vec<StorageSlot>, Box<dyn std::error::Error>> {
+        Ok(vec![StorageSlot::with_value(
+            "counter::counter_slot".parse()?,
+            Word::default(),
+        )])
+    };
+    let predict = |seed, slots| {
+        predict_account_id(
+            &code,
+            slots,
+            seed,
+            AccountType::RegularAccountImmutableCode,
+            AccountStorageMode::Public,
+        )
+    };
+
+    let id = predict([7_u8; 32], slots()?).await?;
+    assert_eq!(predict([7_u8; 32], slots()?).await?, id);
+    assert_ne!(predict([8_u8; 32], slots()?).await?, id);
+    assert_eq!(id.account_type(), AccountType::RegularAccountImmutableCode);
+    assert_eq!(id.storage_mode(), AccountStorageMode::Public);
+
+    let other_code = code.replace("add.1", "add.2");
+    assert_ne!(other_code, code);
+    let other = predict_account_id(
+        &other_code,
+        slots()?,
+        [7_u8; 32],
+        AccountType::RegularAccountImmutableCode,
+        AccountStorageMode::Public,
+    )
+    .await?;
+    assert_ne!(other, id);
+
+    Ok(())
+}
+
+#[test]
+fn account_type_is_decoded_from_the_id() {
+    let account_id = parse_account_id(TESTNET_COUNTER, Network::Testnet).unwrap();
+    assert_eq!(
+        account_type_of(account_id),
+        AccountType::RegularAccountImmutableCode
+    );
+    expect_account_type(account_id, AccountType::RegularAccountImmutableCode).unwrap();
+
+    assert!(matches!(
+        expect_account_type(account_id, AccountType::FungibleFaucet),
+        Err(CommonError::UnexpectedAccountType {
+            actual: AccountType::RegularAccountImmutableCode,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn default_counter_is_testnet_only() {
+    let dir = common::test_dir("missing_manifest");
+    let no_manifest = dir.join("manifest.json");
+    let testnet_counter = parse_account_id(TESTNET_COUNTER, Network::Testnet).unwrap();
+
+    assert_eq!(
+        resolve_counter_id(None, Network::Testnet, &no_manifest).unwrap(),
+        testnet_counter
+    );
+    assert_eq!(
+        resolve_counter_id(Some(TESTNET_COUNTER), Network::Testnet, &no_manifest).unwrap(),
+        testnet_counter
+    );
+
+    let err = resolve_counter_id(None, Network::Localhost, &no_manifest).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<CommonError>(),
+        Some(CommonError::NoDefaultCounter(Network::Localhost))
+    ));
+    std::fs::remove_dir(&dir).unwrap();
+}
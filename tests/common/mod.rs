@@ -0,0 +1,214 @@
+// Fixtures shared by the integration tests. Each test crate uses only some of them
+#![allow(dead_code)]
+
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use masm_project_template::common::create_no_auth_component;
+use miden_client::{
+    Felt, Word,
+    account::{Account, AccountBuilder, AccountId, AccountStorageMode, AccountType},
+    asset::TokenSymbol,
+    block::BlockNumber,
+    note::{Note, NoteScript},
+    transaction::{ExecutedTransaction, TransactionKernel},
+};
+use miden_protocol::{
+    MastForest,
+    account::{PartialAccount, StorageMapWitness},
+    asset::{AssetVaultKey, AssetWitness},
+    block::{BlockHeader, FeeParameters},
+    crypto::{
+        dsa::ecdsa_k256_keccak::SecretKey,
+        merkle::mmr::{Mmr, PartialMmr},
+    },
+    transaction::{AccountInputs, InputNote, InputNotes, PartialBlockchain, TransactionArgs},
+};
+use miden_standards::account::{faucets::BasicFungibleFaucet, wallets::BasicWallet};
+use miden_tx::{
+    DataStore, DataStoreError, MastForestStore, TransactionExecutor, TransactionExecutorError,
+    TransactionMastStore, auth::UnreachableAuth,
+};
+
+// Fresh, empty directory for one test's files. The pid keeps concurrent runs (parallel test
+// binaries, several checkouts) apart and `name` keeps the tests of one binary apart
+pub fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("masm_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).expect("temp dir");
+    dir
+}
+
+// Removes a test's directory unless `KEEP_STATE` is set, so a failed run can be inspected
+pub fn remove_test_dir(dir: &Path) {
+    if std::env::var_os("KEEP_STATE").is_some() {
+        eprintln!("KEEP_STATE set, keeping {}", dir.display());
+        return;
+    }
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+// Public wallet with the template's no-auth component, so it can be built & used without keys
+pub async fn no_auth_wallet(
+    seed: u8,
+    account_type: AccountType,
+) -> Result<Account, Box<dyn std::error::Error>> {
+    Ok(AccountBuilder::new([seed; 32])
+        .account_type(account_type)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(create_no_auth_component(true).await?)
+        .with_component(BasicWallet)
+        .build()?)
+}
+
+// Executes (without proving) a transaction in which `account` consumes `notes` as unauthenticated
+// notes at block `block_num`, against a made-up chain of that many blocks, so note scripts can be
+// run without a node
+pub async fn execute_notes(
+    account: &Account,
+    notes: Vec<Note>,
+    block_num: u32,
+) -> Result<ExecutedTransaction, TransactionExecutorError> {
+    let native_faucet = AccountBuilder::new([0xfe; 32])
+        .account_type(AccountType::FungibleFaucet)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(
+            create_no_auth_component(true)
+                .await
+                .expect("no-auth component"),
+        )
+        .with_component(
+            BasicFungibleFaucet::new(TokenSymbol::new("FEE").expect("symbol"), 0, Felt::new(1))
+                .expect("faucet"),
+        )
+        .build()
+        .expect("faucet account");
+    let store = OfflineStore::new(account, native_faucet.id(), block_num);
+    let notes = InputNotes::new(notes.into_iter().map(InputNote::unauthenticated).collect())
+        .expect("notes are unique");
+
+    TransactionExecutor::<_, UnreachableAuth>::new(&store)
+        .execute_transaction(
+            account.id(),
+            BlockNumber::from(block_num),
+            notes,
+            TransactionArgs::default(),
+        )
+        .await
+}
+
+// Data store holding one account and a reference block whose chain commitment opens the
+// preceding blocks, standing in for the client's store
+struct OfflineStore {
+    account: Account,
+    block_header: BlockHeader,
+    partial_mmr: PartialMmr,
+    mast_store: TransactionMastStore,
+}
+
+impl OfflineStore {
+    fn new(account: &Account, native_asset_id: AccountId, block_num: u32) -> Self {
+        let mut chain = Mmr::new();
+        for block in 0..block_num {
+            chain.add(Word::from([block, 0, 0, 0]));
+        }
+        let peaks = chain.peaks();
+
+        let block_header = BlockHeader::new(
+            0,
+            Word::default(),
+            BlockNumber::from(block_num),
+            peaks.hash_peaks(),
+            Word::default(),
+            Word::default(),
+            Word::default(),
+            Word::default(),
+            TransactionKernel.to_commitment(),
+            SecretKey::new().public_key(),
+            FeeParameters::new(native_asset_id, 0).expect("faucet id"),
+            0,
+        );
+
+        let mast_store = TransactionMastStore::new();
+        mast_store.load_account_code(account.code());
+
+        Self {
+            account: account.clone(),
+            block_header,
+            partial_mmr: PartialMmr::from_peaks(peaks),
+            mast_store,
+        }
+    }
+}
+
+impl MastForestStore for OfflineStore {
+    fn get(&self, procedure_hash: &Word) -> Option<Arc<MastForest>> {
+        self.mast_store.get(procedure_hash)
+    }
+}
+
+impl DataStore for OfflineStore {
+    async fn get_transaction_inputs(
+        &self,
+        account_id: AccountId,
+        _ref_blocks: BTreeSet<BlockNumber>,
+    ) -> Result<(PartialAccount, BlockHeader, PartialBlockchain), DataStoreError> {
+        if account_id != self.account.id() {
+            return Err(DataStoreError::AccountNotFound(account_id));
+        }
+        let partial_blockchain = PartialBlockchain::new(self.partial_mmr.clone(), [])
+            .map_err(|e| DataStoreError::other(e.to_string()))?;
+
+        Ok((
+            PartialAccount::from(&self.account),
+            self.block_header.clone(),
+            partial_blockchain,
+        ))
+    }
+
+    async fn get_foreign_account_inputs(
+        &self,
+        foreign_account_id: AccountId,
+        _ref_block: BlockNumber,
+    ) -> Result<AccountInputs, DataStoreError> {
+        Err(DataStoreError::AccountNotFound(foreign_account_id))
+    }
+
+    async fn get_vault_asset_witnesses(
+        &self,
+        _account_id: AccountId,
+        _vault_root: Word,
+        vault_keys: BTreeSet<AssetVaultKey>,
+    ) -> Result<Vec<AssetWitness>, DataStoreError> {
+        vault_keys
+            .into_iter()
+            .map(|key| {
+                AssetWitness::new(self.account.vault().open(key).into())
+                    .map_err(|e| DataStoreError::other(e.to_string()))
+            })
+            .collect()
+    }
+
+    async fn get_storage_map_witness(
+        &self,
+        account_id: AccountId,
+        map_root: Word,
+        _map_key: Word,
+    ) -> Result<StorageMapWitness, DataStoreError> {
+        Err(DataStoreError::other(format!(
+            "no storage map {} in account {}",
+            map_root, account_id
+        )))
+    }
+
+    // The input notes carry their scripts, so none have to be looked up
+    async fn get_note_script(
+        &self,
+        _script_root: Word,
+    ) -> Result<Option<NoteScript>, DataStoreError> {
+        Ok(None)
+    }
+}
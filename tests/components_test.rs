@@ -0,0 +1,59 @@
+mod common;
+
+use masm_project_template::common::{
+    CommonError, create_no_auth_component, falcon_public_commitment, generate_falcon_key,
+    keystore_has_key_for, validate_components,
+};
+use masm_project_template::prelude::{AuthFalcon512Rpo, BasicWallet};
+use miden_client::{
+    Word,
+    account::{AccountBuilder, AccountComponent, AccountStorageMode, AccountType},
+    auth::AuthSecretKey,
+    crypto::RpoRandomCoin,
+    keystore::FilesystemKeyStore,
+};
+
+#[tokio::test]
+async fn wallet_on_faucet_is_rejected() -> Result<(), Box<dyn std::error::Error>> {
+    let no_auth = create_no_auth_component(true).await?;
+    let wallet = AccountComponent::from(BasicWallet);
+
+    validate_components(
+        AccountType::RegularAccountUpdatableCode,
+        &[no_auth.clone(), wallet.clone()],
+    )?;
+
+    let err =
+        validate_components(AccountType::FungibleFaucet, &[no_auth, wallet.clone()]).unwrap_err();
+    assert!(matches!(err, CommonError::InvalidComponents(_)));
+    assert!(err.to_string().contains("BasicWallet"));
+
+    assert!(validate_components(AccountType::RegularAccountUpdatableCode, &[wallet]).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn keystore_key_lookup_matches_auth_component() -> Result<(), Box<dyn std::error::Error>> {
+    let mut rng = RpoRandomCoin::new(Word::default());
+    let (secret_key, commitment) = generate_falcon_key(&mut rng);
+    assert_eq!(commitment, secret_key.public_key().to_commitment());
+    assert_eq!(falcon_public_commitment(&secret_key), commitment);
+
+    let account = AccountBuilder::new([7_u8; 32])
+        .account_type(AccountType::RegularAccountUpdatableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(AuthFalcon512Rpo::new(commitment.into()))
+        .with_component(BasicWallet)
+        .build()?;
+
+    let dir = common::test_dir("keystore_check");
+    let keystore = FilesystemKeyStore::new(dir.clone())?;
+
+    assert!(!keystore_has_key_for(&keystore, &account)?);
+    keystore.add_key(&AuthSecretKey::Falcon512Rpo(secret_key))?;
+    assert!(keystore_has_key_for(&keystore, &account)?);
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
@@ -1,21 +1,35 @@
+mod common;
+
 use masm_project_template::common::{
-    create_basic_account, create_library, create_public_immutable_contract, create_public_note,
-    create_tx_script, delete_keystore_and_store, instantiate_client, wait_for_note, wait_for_tx,
+    Network, RpcConfig, counter_library_path, create_basic_account, create_library,
+    create_public_immutable_contract, create_public_note, create_tx_script, format_account_id,
+    instantiate_client_with_keystore, keystore_has_key_for, note_tag_for_account, wait_for_note,
+    wait_for_storage_value, wait_for_tx, word_to_u64,
 };
 use miden_client::{
-    ClientError, Word, keystore::FilesystemKeyStore, note::NoteAssets, rpc::Endpoint,
+    Word,
+    account::{Account, StorageSlotName},
+    keystore::FilesystemKeyStore,
+    note::NoteAssets,
+    rpc::Endpoint,
     transaction::TransactionRequestBuilder,
 };
-use miden_protocol::address::NetworkId;
 use std::{fs, path::Path, sync::Arc};
-use tokio::time::{Duration, sleep};
+use tokio::time::Duration;
 
 #[tokio::test]
-async fn increment_counter_with_script() -> Result<(), ClientError> {
-    delete_keystore_and_store().await;
-
+async fn increment_counter_with_script() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = common::test_dir("increment_with_script");
+    let keystore = Arc::new(FilesystemKeyStore::new(dir.join("keystore")).unwrap());
     let endpoint = Endpoint::localhost();
-    let mut client = instantiate_client(endpoint.clone()).await.unwrap();
+    let mut client = instantiate_client_with_keystore(
+        endpoint.clone(),
+        dir.join("store.sqlite3"),
+        keystore.clone(),
+        &RpcConfig::default(),
+    )
+    .await
+    .unwrap();
 
     let sync_summary = client.sync_state().await.unwrap();
     println!("Latest block: {}", sync_summary.block_num);
@@ -25,7 +39,7 @@ async fn increment_counter_with_script() -> Result<(), ClientError> {
     // -------------------------------------------------------------------------
     let counter_code = fs::read_to_string(Path::new("./masm/accounts/counter.masm")).unwrap();
 
-    let counter_contract = create_public_immutable_contract(&mut client, &counter_code)
+    let counter_contract = create_public_immutable_contract(&mut client, &counter_code, true)
         .await
         .unwrap();
     println!("contract id: {:?}", counter_contract.id().to_hex());
@@ -38,9 +52,9 @@ async fn increment_counter_with_script() -> Result<(), ClientError> {
     let script_code =
         fs::read_to_string(Path::new("./masm/scripts/increment_script.masm")).unwrap();
 
-    let library_path = "external_contract::counter_contract";
+    let library_path = counter_library_path();
 
-    let library = create_library(counter_code, library_path).unwrap();
+    let library = create_library(counter_code, library_path, true).unwrap();
 
     let tx_script = create_tx_script(script_code, Some(library)).unwrap();
 
@@ -62,44 +76,60 @@ async fn increment_counter_with_script() -> Result<(), ClientError> {
     // -------------------------------------------------------------------------
     // STEP 4: Validate Updated State
     // -------------------------------------------------------------------------
-    sleep(Duration::from_secs(7)).await;
-
-    delete_keystore_and_store().await;
-
-    let mut client = instantiate_client(endpoint).await.unwrap();
-
-    client
-        .import_account_by_id(counter_contract.id())
-        .await
-        .unwrap();
-
-    let new_account_record = client
+    // A fresh store, so the counter's state is read back from the node
+    let mut client = instantiate_client_with_keystore(
+        endpoint,
+        dir.join("fresh_store.sqlite3"),
+        keystore,
+        &RpcConfig::default(),
+    )
+    .await
+    .unwrap();
+
+    wait_for_storage_value(
+        &mut client,
+        counter_contract.id(),
+        "counter::counter_slot",
+        3,
+        1,
+        Duration::from_secs(30),
+    )
+    .await
+    .unwrap();
+
+    let new_account_record: Account = client
         .get_account(counter_contract.id())
         .await
         .unwrap()
+        .unwrap()
+        .try_into()
         .unwrap();
 
+    let storage_slot_name = StorageSlotName::new("counter::counter_slot").unwrap();
     let count: Word = new_account_record
-        .account_data()
         .storage()
-        .get_item(0)
-        .unwrap()
-        .into();
-    let val = count.get(3).unwrap().as_int();
+        .get_item(&storage_slot_name)
+        .unwrap();
+    let val = word_to_u64(&count);
     assert_eq!(val, 1);
 
+    common::remove_test_dir(&dir);
     Ok(())
 }
 
 #[tokio::test]
-async fn increment_counter_with_note() -> Result<(), ClientError> {
-    delete_keystore_and_store().await;
-
+async fn increment_counter_with_note() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = common::test_dir("increment_with_note");
+    let keystore = Arc::new(FilesystemKeyStore::new(dir.join("keystore")).unwrap());
     let endpoint = Endpoint::localhost();
-    let mut client = instantiate_client(endpoint.clone()).await.unwrap();
-
-    let keystore_path = std::path::PathBuf::from("./keystore");
-    let keystore = Arc::new(FilesystemKeyStore::new(keystore_path).unwrap());
+    let mut client = instantiate_client_with_keystore(
+        endpoint.clone(),
+        dir.join("store.sqlite3"),
+        keystore.clone(),
+        &RpcConfig::default(),
+    )
+    .await
+    .unwrap();
 
     let sync_summary = client.sync_state().await.unwrap();
     println!("Latest block: {}", sync_summary.block_num);
@@ -107,11 +137,19 @@ async fn increment_counter_with_note() -> Result<(), ClientError> {
     // -------------------------------------------------------------------------
     // STEP 1: Create Basic User Account
     // -------------------------------------------------------------------------
-    let (alice_account, _) = create_basic_account(&mut client, &keystore).await.unwrap();
+    let alice_account = create_basic_account(&mut client, &keystore)
+        .await
+        .unwrap()
+        .account;
+    assert!(
+        keystore_has_key_for(&keystore, &alice_account).unwrap(),
+        "missing key for account {}",
+        alice_account.id()
+    );
 
     println!(
         "alice account id: {:?}",
-        alice_account.id().to_bech32(NetworkId::Testnet)
+        format_account_id(alice_account.id(), Network::Localhost)
     );
 
     // -------------------------------------------------------------------------
@@ -119,13 +157,13 @@ async fn increment_counter_with_note() -> Result<(), ClientError> {
     // -------------------------------------------------------------------------
     let counter_code = fs::read_to_string(Path::new("./masm/accounts/counter.masm")).unwrap();
 
-    let counter_contract = create_public_immutable_contract(&mut client, &counter_code)
+    let counter_contract = create_public_immutable_contract(&mut client, &counter_code, true)
         .await
         .unwrap();
 
     println!(
         "contract id: {:?}",
-        counter_contract.id().to_bech32(NetworkId::Testnet)
+        format_account_id(counter_contract.id(), Network::Localhost)
     );
 
     client.add_account(&counter_contract, false).await.unwrap();
@@ -137,9 +175,16 @@ async fn increment_counter_with_note() -> Result<(), ClientError> {
 
     let note_assets = NoteAssets::new(vec![]).unwrap();
 
-    let increment_note = create_public_note(&mut client, note_code, alice_account, note_assets)
-        .await
-        .unwrap();
+    let increment_note = create_public_note(
+        &mut client,
+        note_code,
+        alice_account,
+        note_tag_for_account(counter_contract.id()),
+        note_assets,
+        true,
+    )
+    .await
+    .unwrap();
 
     println!("increment note created, waiting for onchain commitment");
 
@@ -169,29 +214,37 @@ async fn increment_counter_with_note() -> Result<(), ClientError> {
     // -------------------------------------------------------------------------
     // STEP 5: Validate Updated State
     // -------------------------------------------------------------------------
-    sleep(Duration::from_secs(5)).await;
-
-    delete_keystore_and_store().await;
-
-    let mut client = instantiate_client(endpoint).await.unwrap();
-
-    client
-        .import_account_by_id(counter_contract.id())
-        .await
-        .unwrap();
+    // A fresh store, so the counter's state is read back from the node
+    let mut client = instantiate_client_with_keystore(
+        endpoint,
+        dir.join("fresh_store.sqlite3"),
+        keystore,
+        &RpcConfig::default(),
+    )
+    .await
+    .unwrap();
+
+    wait_for_storage_value(
+        &mut client,
+        counter_contract.id(),
+        "counter::counter_slot",
+        3,
+        1,
+        Duration::from_secs(30),
+    )
+    .await
+    .unwrap();
 
     let new_account_record = client.get_account(counter_contract.id()).await.unwrap();
 
-    if let Some(account_record) = new_account_record.as_ref() {
-        let count: Word = account_record
-            .account_data()
-            .storage()
-            .get_item(0)
-            .unwrap()
-            .into();
-        let val = count.get(3).unwrap().as_int();
+    if let Some(account_record) = new_account_record {
+        let account: Account = account_record.try_into().unwrap();
+        let storage_slot_name = StorageSlotName::new("counter::counter_slot").unwrap();
+        let count: Word = account.storage().get_item(&storage_slot_name).unwrap();
+        let val = word_to_u64(&count);
         assert_eq!(val, 1);
     }
 
+    common::remove_test_dir(&dir);
     Ok(())
 }
@@ -0,0 +1,95 @@
+mod common;
+
+use masm_project_template::common::{
+    COUNTER_LIBRARY_PATH, CommonError, build_counter_artifacts, check_masm, check_masm_dir,
+    check_masm_file, create_library, load_library, procedure_roots, save_library,
+};
+use std::{fs, path::Path};
+
+#[test]
+fn counter_contract_assembles() {
+    check_masm_file(Path::new("./masm/accounts/counter.masm")).unwrap();
+}
+
+#[test]
+fn invalid_masm_is_rejected() {
+    let code = "pub proc broken\n    push.1 not_an_instruction\nend\n";
+    assert!(check_masm(code, "broken").is_err());
+}
+
+#[test]
+fn counter_library_exports_procedures() {
+    let code = fs::read_to_string(Path::new("./masm/accounts/counter.masm")).unwrap();
+    let library = create_library(code, COUNTER_LIBRARY_PATH, true).unwrap();
+
+    let roots = procedure_roots(&library);
+    let paths: Vec<&str> = roots.iter().map(|(path, _)| path.as_str()).collect();
+    assert!(paths.contains(&"external_contract::counter_contract::increment"));
+    assert!(paths.contains(&"external_contract::counter_contract::get_count"));
+    for (path, root) in &roots {
+        assert_eq!(
+            library.get_procedure_root_by_path(path.as_str()),
+            Some(*root)
+        );
+    }
+}
+
+#[test]
+fn counter_note_and_script_share_library() {
+    let account_code = fs::read_to_string("./masm/accounts/counter.masm").unwrap();
+    let note_code = fs::read_to_string("./masm/notes/increment_note.masm").unwrap();
+    let script_code = fs::read_to_string("./masm/scripts/increment_script.masm").unwrap();
+
+    let artifacts = build_counter_artifacts(&account_code, &note_code, &script_code).unwrap();
+    assert!(
+        procedure_roots(&artifacts.library)
+            .iter()
+            .any(|(path, _)| path == "external_contract::counter_contract::increment")
+    );
+}
+
+#[test]
+fn masm_dir_assembles_and_reports_failures() {
+    let code = fs::read_to_string("./masm/accounts/counter.masm").unwrap();
+    let library = create_library(code, COUNTER_LIBRARY_PATH, true).unwrap();
+    let libraries = [library];
+    check_masm_dir(Path::new("./masm"), &libraries).unwrap();
+
+    let dir = common::test_dir("dir_check");
+    fs::write(
+        dir.join("broken.masm"),
+        "begin\n    not_an_instruction\nend\n",
+    )
+    .unwrap();
+    fs::write(dir.join("ok.masm"), "begin\n    nop\nend\n").unwrap();
+
+    let failures = check_masm_dir(&dir, &libraries).unwrap_err();
+    assert_eq!(failures.len(), 1);
+    assert!(failures[0].0.ends_with("broken.masm"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn saved_library_loads_back() -> Result<(), Box<dyn std::error::Error>> {
+    let counter_code = fs::read_to_string("./masm/accounts/counter.masm")?;
+    let library = create_library(counter_code, COUNTER_LIBRARY_PATH, true)?;
+    let dir = common::test_dir("counter_library");
+    let path = dir.join("counter_library.masl");
+
+    save_library(&library, &path)?;
+    assert_eq!(
+        procedure_roots(&load_library(&path)?),
+        procedure_roots(&library)
+    );
+
+    fs::write(&path, b"not a library")?;
+    let err = load_library(&path).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<CommonError>(),
+        Some(CommonError::InvalidLibraryFile { .. })
+    ));
+
+    fs::remove_dir_all(&dir)?;
+    Ok(())
+}
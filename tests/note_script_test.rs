@@ -0,0 +1,292 @@
+mod common;
+
+use masm_project_template::common::{
+    CommonError, MerkleOpening, Network, already_consumed_note, assert_note_script,
+    create_no_auth_component, describe_note, ensure_supported_note_type, merkle_note_inputs,
+    note_assets, note_script_matches, p2id_target, parse_account_id, recipient_digest,
+    timelock_note_inputs,
+};
+use miden_client::{
+    ClientError, Felt, Word,
+    account::{AccountBuilder, AccountId, AccountStorageMode, AccountType},
+    assembly::CodeBuilder,
+    asset::TokenSymbol,
+    crypto::{MerkleTree, NodeIndex},
+    note::{Note, NoteAssets, NoteId, NoteInputs, NoteMetadata, NoteRecipient, NoteTag, NoteType},
+    store::InputNoteRecord,
+    transaction::TransactionRequestError,
+};
+use miden_standards::{account::faucets::BasicFungibleFaucet, note::utils::build_p2id_recipient};
+
+#[tokio::test]
+async fn note_script_mismatch_is_detected() -> Result<(), Box<dyn std::error::Error>> {
+    let creator = common::no_auth_wallet(0, AccountType::RegularAccountImmutableCode).await?;
+
+    let nop_script = CodeBuilder::new().compile_note_script("begin nop end")?;
+    let push_script = CodeBuilder::new().compile_note_script("begin push.1 drop end")?;
+
+    let build_note = |script, serial: u32| -> Result<Note, Box<dyn std::error::Error>> {
+        let recipient = NoteRecipient::new(
+            Word::from([serial, 0, 0, 0]),
+            script,
+            NoteInputs::new(vec![])?,
+        );
+        let metadata = NoteMetadata::new(creator.id(), NoteType::Public, NoteTag::new(0));
+        Ok(Note::new(NoteAssets::new(vec![])?, metadata, recipient))
+    };
+
+    let nop_note = build_note(nop_script.clone(), 1)?;
+    let push_note = build_note(push_script.clone(), 2)?;
+
+    assert!(note_script_matches(&nop_note, &nop_script));
+    assert!(!note_script_matches(&push_note, &nop_script));
+
+    assert_note_script(&push_note, &push_script)?;
+    assert!(matches!(
+        assert_note_script(&nop_note, &push_script),
+        Err(CommonError::NoteScriptMismatch { .. })
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn recipient_digest_tracks_serial_script_and_inputs() -> Result<(), Box<dyn std::error::Error>> {
+    let script = CodeBuilder::new().compile_note_script("begin nop end")?;
+    let recipient =
+        |serial: u32, inputs: Vec<u64>| -> Result<NoteRecipient, Box<dyn std::error::Error>> {
+            Ok(NoteRecipient::new(
+                Word::from([serial, 0, 0, 0]),
+                script.clone(),
+                NoteInputs::new(inputs.into_iter().map(Felt::new).collect())?,
+            ))
+        };
+
+    let digest = recipient_digest(&recipient(1, vec![7])?);
+    assert_eq!(digest, recipient_digest(&recipient(1, vec![7])?));
+    assert_ne!(digest, recipient_digest(&recipient(2, vec![7])?));
+    assert_ne!(digest, recipient_digest(&recipient(1, vec![8])?));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn note_assets_are_built_and_described() -> Result<(), Box<dyn std::error::Error>> {
+    let mut faucet_ids: Vec<AccountId> = Vec::new();
+    for (seed, symbol) in [(1_u8, "TOKA"), (2, "TOKB")] {
+        let faucet = AccountBuilder::new([seed; 32])
+            .account_type(AccountType::FungibleFaucet)
+            .storage_mode(AccountStorageMode::Public)
+            .with_auth_component(create_no_auth_component(true).await?)
+            .with_component(BasicFungibleFaucet::new(
+                TokenSymbol::new(symbol)?,
+                8,
+                Felt::new(1_000_000),
+            )?)
+            .build()?;
+        faucet_ids.push(faucet.id());
+    }
+
+    let assets = note_assets(&[(faucet_ids[0], 100), (faucet_ids[1], 5)])?;
+    assert_eq!(assets.num_assets(), 2);
+
+    assert!(note_assets(&[(faucet_ids[0], 1), (faucet_ids[0], 2)]).is_err());
+
+    let script = CodeBuilder::new().compile_note_script("begin nop end")?;
+    let recipient = NoteRecipient::new(
+        Word::default(),
+        script,
+        NoteInputs::new(vec![Felt::new(42)])?,
+    );
+    let metadata = NoteMetadata::new(faucet_ids[0], NoteType::Private, NoteTag::new(0));
+    let record = InputNoteRecord::from(Note::new(assets, metadata, recipient));
+
+    let summary = describe_note(&record);
+    assert_eq!(summary.sender, Some(faucet_ids[0]));
+    assert_eq!(summary.note_type, Some(NoteType::Private));
+    assert_eq!(
+        summary.assets,
+        vec![(faucet_ids[0], 100), (faucet_ids[1], 5)]
+    );
+    assert_eq!(summary.inputs, vec![Felt::new(42)]);
+    assert!(summary.to_string().contains("100 of faucet"));
+
+    Ok(())
+}
+
+#[test]
+fn encrypted_notes_are_rejected() {
+    ensure_supported_note_type(NoteType::Public).unwrap();
+    ensure_supported_note_type(NoteType::Private).unwrap();
+    assert!(matches!(
+        ensure_supported_note_type(NoteType::Encrypted),
+        Err(CommonError::UnsupportedNoteType(NoteType::Encrypted))
+    ));
+}
+
+#[tokio::test]
+async fn p2id_target_is_decoded() -> Result<(), Box<dyn std::error::Error>> {
+    let target = common::no_auth_wallet(9, AccountType::RegularAccountImmutableCode).await?;
+    let metadata = NoteMetadata::new(target.id(), NoteType::Public, NoteTag::new(0));
+
+    let recipient = build_p2id_recipient(target.id(), Word::default())?;
+    let p2id = Note::new(NoteAssets::new(vec![])?, metadata.clone(), recipient);
+    assert_eq!(p2id_target(&p2id)?, target.id());
+
+    let script = CodeBuilder::new().compile_note_script("begin nop end")?;
+    let recipient = NoteRecipient::new(Word::default(), script, NoteInputs::new(vec![])?);
+    let other = Note::new(NoteAssets::new(vec![])?, metadata, recipient);
+    assert!(matches!(
+        p2id_target(&other)
+            .unwrap_err()
+            .downcast_ref::<CommonError>(),
+        Some(CommonError::NotAP2idNote(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn merkle_note_inputs_carry_the_opening() -> Result<(), Box<dyn std::error::Error>> {
+    let target = parse_account_id("mtst1azxmwd8waj5cuqq24h995zc73snfrp89", Network::Testnet)?;
+    let leaves: Vec<Word> = (0..8_u32).map(|i| Word::from([i, 0, 0, 0])).collect();
+    let tree = MerkleTree::new(leaves.clone())?;
+
+    let opening = MerkleOpening {
+        leaf: leaves[5],
+        index: 5,
+        proof: tree.get_path(NodeIndex::new(3, 5)?)?,
+    };
+    assert_eq!(opening.root()?, tree.root());
+
+    let inputs = merkle_note_inputs(target, &opening)?;
+    let values = inputs.values();
+    assert_eq!(values.len(), 12 + 4 * 3);
+    assert_eq!(values[2], Felt::new(5));
+    assert_eq!(values[3], Felt::new(3));
+    assert_eq!(&values[4..8], tree.root().as_elements());
+    assert_eq!(&values[8..12], leaves[5].as_elements());
+
+    let wrong_index = MerkleOpening {
+        index: 8,
+        ..opening
+    };
+    assert!(matches!(
+        merkle_note_inputs(target, &wrong_index),
+        Err(CommonError::InvalidMerkleProof(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn already_consumed_notes_are_recognised() -> Result<(), Box<dyn std::error::Error>> {
+    let script = CodeBuilder::new().compile_note_script("begin nop end")?;
+    let recipient = NoteRecipient::new(Word::default(), script, NoteInputs::new(vec![])?);
+    let note_id = NoteId::new(recipient.digest(), NoteAssets::new(vec![])?.commitment());
+
+    let err = ClientError::TransactionRequestError(
+        TransactionRequestError::InputNoteAlreadyConsumed(note_id),
+    );
+    assert_eq!(already_consumed_note(&err), Some(note_id));
+    assert_eq!(
+        already_consumed_note(&ClientError::AccountDataNotFound(parse_account_id(
+            "mtst1azxmwd8waj5cuqq24h995zc73snfrp89",
+            Network::Testnet
+        )?)),
+        None
+    );
+
+    Ok(())
+}
+
+// Note from one of the template's note scripts, sent by `sender`, with no assets
+fn template_note(
+    path: &str,
+    sender: AccountId,
+    inputs: NoteInputs,
+) -> Result<Note, Box<dyn std::error::Error>> {
+    let script = CodeBuilder::new().compile_note_script(std::fs::read_to_string(path)?)?;
+    let recipient = NoteRecipient::new(Word::from([1_u32, 2, 3, 4]), script, inputs);
+    let metadata = NoteMetadata::new(sender, NoteType::Public, NoteTag::new(0));
+    Ok(Note::new(NoteAssets::new(vec![])?, metadata, recipient))
+}
+
+#[tokio::test]
+async fn merkle_note_script_checks_the_opening() -> Result<(), Box<dyn std::error::Error>> {
+    let target = common::no_auth_wallet(0x31, AccountType::RegularAccountUpdatableCode).await?;
+    let other = common::no_auth_wallet(0x32, AccountType::RegularAccountUpdatableCode).await?;
+    let leaves: Vec<Word> = (0..8_u32).map(|i| Word::from([i, 0, 0, 0])).collect();
+    let tree = MerkleTree::new(leaves.clone())?;
+    let opening = MerkleOpening {
+        leaf: leaves[5],
+        index: 5,
+        proof: tree.get_path(NodeIndex::new(3, 5)?)?,
+    };
+    let inputs = merkle_note_inputs(target.id(), &opening)?;
+    let note = |inputs| template_note("./masm/notes/merkle_note.masm", other.id(), inputs);
+
+    common::execute_notes(&target, vec![note(inputs.clone())?], 1).await?;
+
+    let err = common::execute_notes(&other, vec![note(inputs.clone())?], 1)
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("can only be consumed by its target")
+    );
+
+    // [target, index, depth] come first, then ROOT at 4..8 and the first sibling at 12..16
+    for tampered in [4, 12] {
+        let mut values = inputs.values().to_vec();
+        values[tampered] += Felt::new(1);
+        let err = common::execute_notes(&target, vec![note(NoteInputs::new(values)?)?], 1)
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("does not open its leaf to its root"),
+            "{}",
+            err
+        );
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn timelock_note_switches_consumer_at_the_deadline() -> Result<(), Box<dyn std::error::Error>>
+{
+    let target = common::no_auth_wallet(0x41, AccountType::RegularAccountUpdatableCode).await?;
+    let sender = common::no_auth_wallet(0x42, AccountType::RegularAccountUpdatableCode).await?;
+    let deadline = 10;
+    let note = || {
+        template_note(
+            "./masm/notes/timelock_note.masm",
+            sender.id(),
+            timelock_note_inputs(target.id(), deadline)?,
+        )
+    };
+
+    // Before the deadline only the target can consume it
+    common::execute_notes(&target, vec![note()?], deadline - 1).await?;
+    let err = common::execute_notes(&sender, vec![note()?], deadline - 1)
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("can only be consumed by its target")
+    );
+
+    // From the deadline on only the sender can reclaim it
+    common::execute_notes(&sender, vec![note()?], deadline).await?;
+    let err = common::execute_notes(&target, vec![note()?], deadline)
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("can only be reclaimed by its sender")
+    );
+
+    Ok(())
+}
@@ -0,0 +1,83 @@
+mod common;
+
+use masm_project_template::{
+    common::{
+        CommonError, Intent, build_intent, compile_tx_script_templated, describe_request,
+        instantiate_client,
+    },
+    prelude::*,
+};
+use miden_client::transaction::TransactionRequestBuilder;
+
+const TEMPLATE: &str = "begin\n    push.{{amount}} push.{{fee}} drop drop\nend\n";
+
+#[test]
+fn templated_script_compiles_once_filled() {
+    compile_tx_script_templated(
+        TEMPLATE,
+        &[("amount", "100".to_string()), ("fee", "1".to_string())],
+        &[],
+    )
+    .unwrap();
+}
+
+#[test]
+fn unfilled_placeholder_is_reported() {
+    let err =
+        compile_tx_script_templated(TEMPLATE, &[("amount", "100".to_string())], &[]).unwrap_err();
+
+    match err.downcast_ref::<CommonError>() {
+        Some(CommonError::UnfilledPlaceholder(name)) => assert_eq!(name, "fee"),
+        other => panic!("unexpected error: {:?}", other),
+    }
+}
+
+#[test]
+fn request_description_lists_script() {
+    let script = compile_tx_script_templated(
+        TEMPLATE,
+        &[("amount", "100".to_string()), ("fee", "1".to_string())],
+        &[],
+    )
+    .unwrap();
+    let request = TransactionRequestBuilder::new()
+        .custom_script(script)
+        .build()
+        .unwrap();
+
+    let description = describe_request(&request);
+    assert!(description.contains("input notes: 0"));
+    assert!(description.contains("script: custom"));
+}
+
+#[tokio::test]
+async fn increment_intent_uses_given_script() -> Result<(), Box<dyn std::error::Error>> {
+    let mut client = instantiate_client(
+        Endpoint::localhost(),
+        StoreBackend::in_memory(),
+        &RpcConfig::default(),
+    )
+    .await?;
+    let account = common::no_auth_wallet(5, AccountType::RegularAccountImmutableCode).await?;
+
+    let request = build_intent(
+        &mut client,
+        &account,
+        Intent::Increment {
+            script_path: "./masm/scripts/increment_script.masm".into(),
+        },
+    )
+    .await?;
+    assert!(describe_request(&request).contains("script: custom"));
+
+    let missing = build_intent(
+        &mut client,
+        &account,
+        Intent::Increment {
+            script_path: "./masm/scripts/missing_script.masm".into(),
+        },
+    )
+    .await;
+    assert!(missing.is_err());
+    Ok(())
+}
@@ -0,0 +1,210 @@
+mod common;
+
+use masm_project_template::common::{
+    COUNTER_SLOT_NAME, CommonError, assert_account_matches, check_mint_supply, create_library,
+    create_no_auth_component, describe_contract, diff_snapshots, dump_storage, read_storage_map,
+    read_storage_value, save_account_state, snapshot_account, storage_slots_in_source,
+    verify_contract_layout, word_from_u64, word_to_u64,
+};
+use miden_client::{
+    Felt, Word,
+    account::{
+        Account, AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageMap,
+        StorageSlot, StorageSlotName,
+    },
+    asset::TokenSymbol,
+};
+use miden_protocol::account::AccountComponentCode;
+use miden_standards::account::faucets::BasicFungibleFaucet;
+use std::fs;
+
+async fn account_with_map(key: Word, value: Word) -> Result<Account, Box<dyn std::error::Error>> {
+    let counter_code = fs::read_to_string("./masm/accounts/counter.masm")?;
+    let library = create_library(counter_code, "counter", true)?;
+
+    let component = AccountComponent::new(
+        AccountComponentCode::from(library),
+        vec![
+            StorageSlot::with_value("counter::counter_slot".parse()?, Word::default()),
+            StorageSlot::with_map(
+                "counter::balances".parse()?,
+                StorageMap::with_entries([(key, value)].into_iter())?,
+            ),
+        ],
+    )?
+    .with_supports_all_types();
+
+    Ok(AccountBuilder::new([1_u8; 32])
+        .account_type(AccountType::RegularAccountImmutableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(create_no_auth_component(true).await?)
+        .with_component(component)
+        .build()?)
+}
+
+#[tokio::test]
+async fn reads_map_entries_and_rejects_value_slots() -> Result<(), Box<dyn std::error::Error>> {
+    let key = Word::from([1_u32, 2, 3, 4]);
+    let value = word_from_u64(42);
+    let account = account_with_map(key, value).await?;
+
+    assert_eq!(read_storage_map(&account, "counter::balances", key)?, value);
+    assert_eq!(word_to_u64(&value), 42);
+
+    let err = read_storage_map(&account, "counter::counter_slot", key).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<CommonError>(),
+        Some(CommonError::NotAMapSlot(_))
+    ));
+
+    assert_eq!(
+        read_storage_value(&account, COUNTER_SLOT_NAME)?,
+        Word::default()
+    );
+    let err = read_storage_value(&account, "counter::balances").unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<CommonError>(),
+        Some(CommonError::NotAValueSlot(_))
+    ));
+
+    let dump = dump_storage(&account);
+    assert!(
+        dump.iter()
+            .any(|(name, word)| name.starts_with("counter::balances[") && *word == value)
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn diff_reports_changed_map_entry() -> Result<(), Box<dyn std::error::Error>> {
+    let key = Word::from([1_u32, 2, 3, 4]);
+    let before = snapshot_account(&account_with_map(key, word_from_u64(1)).await?);
+    let after = snapshot_account(&account_with_map(key, word_from_u64(2)).await?);
+
+    let diff = diff_snapshots(&before, &after);
+    assert!(diff.storage.iter().any(|(slot, old, new)| {
+        slot.starts_with("counter::balances[")
+            && *old == Some(word_from_u64(1))
+            && *new == Some(word_from_u64(2))
+    }));
+    assert!(
+        !diff
+            .storage
+            .iter()
+            .any(|(slot, _, _)| slot == "counter::counter_slot")
+    );
+    assert!(diff.balances.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn counter_declares_its_slot() -> Result<(), Box<dyn std::error::Error>> {
+    let counter_code = fs::read_to_string("./masm/accounts/counter.masm")?;
+
+    let slots = storage_slots_in_source(&counter_code)?;
+    assert_eq!(slots, vec![StorageSlotName::new("counter::counter_slot")?]);
+    assert!(storage_slots_in_source("const X = word(\"not a slot\")").is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn mints_past_max_supply_are_rejected() -> Result<(), Box<dyn std::error::Error>> {
+    let faucet = AccountBuilder::new([5_u8; 32])
+        .account_type(AccountType::FungibleFaucet)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(create_no_auth_component(true).await?)
+        .with_component(BasicFungibleFaucet::new(
+            TokenSymbol::new("CNT")?,
+            0,
+            Felt::new(1_000),
+        )?)
+        .build()?;
+
+    check_mint_supply(&faucet, 1_000)?;
+    let err = check_mint_supply(&faucet, 1_001).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<CommonError>(),
+        Some(CommonError::ExceedsMaxSupply {
+            issued: 0,
+            max_supply: 1_000,
+            ..
+        })
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn contract_layout_reports_missing_slots() -> Result<(), Box<dyn std::error::Error>> {
+    let account = account_with_map(Word::default(), Word::default()).await?;
+    verify_contract_layout(&account, &["counter::counter_slot", "counter::balances"])?;
+
+    let err =
+        verify_contract_layout(&account, &["counter::counter_slot", "counter::owner"]).unwrap_err();
+    match err {
+        CommonError::UnexpectedStorageLayout { missing, found, .. } => {
+            assert_eq!(missing, vec!["counter::owner".to_string()]);
+            assert!(found.contains(&"counter::balances".to_string()));
+        }
+        other => panic!("unexpected error: {}", other),
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn contract_description_lists_procedures_and_storage()
+-> Result<(), Box<dyn std::error::Error>> {
+    let counter_code = fs::read_to_string("./masm/accounts/counter.masm")?;
+    let library = create_library(counter_code, "counter", true)?;
+    let account = account_with_map(Word::default(), Word::default()).await?;
+
+    let offline = describe_contract(&library, None);
+    assert!(
+        offline
+            .procedures
+            .iter()
+            .any(|(path, _)| path == "counter::increment")
+    );
+    assert!(offline.storage.is_empty());
+
+    let deployed = describe_contract(&library, Some(&account));
+    assert_eq!(deployed.procedures, offline.procedures);
+    assert_eq!(deployed.account_id, Some(account.id()));
+    assert!(deployed.to_string().contains("counter::counter_slot"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn saved_account_state_is_compared() -> Result<(), Box<dyn std::error::Error>> {
+    let account = account_with_map(Word::default(), Word::from([1_u32, 0, 0, 0])).await?;
+    let dir = common::test_dir("account_state");
+    let path = dir.join("account_state.json");
+
+    save_account_state(&account, &path)?;
+    assert_account_matches(&account, &path)?;
+
+    let mut saved: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path)?)?;
+    saved["storage"]["counter::counter_slot"] = word_from_u64(7).to_hex().into();
+    fs::write(&path, saved.to_string())?;
+    match assert_account_matches(&account, &path) {
+        Err(CommonError::StateMismatch { diff, .. }) => {
+            assert_eq!(diff.storage.len(), 1);
+            assert_eq!(diff.storage[0].0, "counter::counter_slot");
+        }
+        other => panic!("expected a state mismatch, got {:?}", other.err()),
+    }
+
+    let other = account_with_map(Word::default(), Word::default()).await?;
+    assert!(matches!(
+        assert_account_matches(&other, &path),
+        Err(CommonError::InvalidSnapshot { .. })
+    ));
+
+    fs::remove_dir_all(&dir)?;
+    Ok(())
+}
@@ -0,0 +1,152 @@
+mod common;
+
+use std::sync::Arc;
+
+use masm_project_template::{
+    common::{
+        assert_accounts_agree, create_basic_account_with_auth, create_wallet_with_component,
+        instantiate_client, is_stale_account_error, keystore_has_key_for, verify_contract_layout,
+    },
+    prelude::*,
+};
+use miden_client::rpc::{GrpcError, NodeRpcClientEndpoint, RpcError};
+
+#[tokio::test]
+async fn in_memory_stores_are_isolated() -> Result<(), Box<dyn std::error::Error>> {
+    let mut first = instantiate_client(
+        Endpoint::localhost(),
+        StoreBackend::in_memory(),
+        &RpcConfig::default(),
+    )
+    .await?;
+    let mut second = instantiate_client(
+        Endpoint::localhost(),
+        StoreBackend::in_memory(),
+        &RpcConfig::default(),
+    )
+    .await?;
+
+    let account = common::no_auth_wallet(3, AccountType::RegularAccountImmutableCode).await?;
+    first.add_account(&account, false).await?;
+
+    assert_eq!(first.get_account_headers().await?.len(), 1);
+    assert!(second.get_account_headers().await?.is_empty());
+    assert!(
+        assert_accounts_agree(&first, &second, account.id())
+            .await
+            .is_err()
+    );
+
+    second.add_account(&account, false).await?;
+    assert_accounts_agree(&first, &second, account.id()).await?;
+
+    Ok(())
+}
+
+#[test]
+fn in_memory_backend_keeps_its_path() {
+    let store = StoreBackend::in_memory();
+
+    assert_eq!(store.sqlite_path(), store.sqlite_path());
+    assert_eq!(store.clone().sqlite_path(), store.sqlite_path());
+    assert_ne!(StoreBackend::in_memory().sqlite_path(), store.sqlite_path());
+}
+
+#[tokio::test]
+async fn connect_timeout_reports_unreachable_node() {
+    let endpoint = Endpoint::new("http".to_string(), "127.0.0.1".to_string(), Some(1));
+    let rpc_config = RpcConfig {
+        connect_timeout_ms: Some(2_000),
+        ..RpcConfig::default()
+    };
+
+    let err = instantiate_client(endpoint, StoreBackend::in_memory(), &rpc_config)
+        .await
+        .err()
+        .expect("no node listens on port 1");
+    assert!(matches!(
+        err.downcast_ref::<CommonError>(),
+        Some(CommonError::EndpointUnreachable { .. })
+    ));
+}
+
+#[tokio::test]
+async fn basic_account_uses_requested_auth_scheme() -> Result<(), Box<dyn std::error::Error>> {
+    let mut client = instantiate_client(
+        Endpoint::localhost(),
+        StoreBackend::in_memory(),
+        &RpcConfig::default(),
+    )
+    .await?;
+    let keystore_dir = common::test_dir("auth_scheme_keystore");
+    let keystore = Arc::new(FilesystemKeyStore::new(keystore_dir.clone())?);
+
+    let created =
+        create_basic_account_with_auth(&mut client, &keystore, AuthSchemeId::EcdsaK256Keccak)
+            .await?;
+    assert!(matches!(
+        created.secret_key,
+        AuthSecretKey::EcdsaK256Keccak(_)
+    ));
+    assert_eq!(
+        created.public_key.to_commitment(),
+        created.secret_key.public_key().to_commitment()
+    );
+    assert!(client.get_account(created.account.id()).await?.is_some());
+
+    std::fs::remove_dir_all(&keystore_dir)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn smart_wallet_has_wallet_and_custom_component() -> Result<(), Box<dyn std::error::Error>> {
+    let mut client = instantiate_client(
+        Endpoint::localhost(),
+        StoreBackend::in_memory(),
+        &RpcConfig::default(),
+    )
+    .await?;
+    let keystore_dir = common::test_dir("smart_wallet_keystore");
+    let keystore = Arc::new(FilesystemKeyStore::new(keystore_dir.clone())?);
+
+    let counter_code = std::fs::read_to_string("./masm/accounts/counter.masm")?;
+    let slots = vec![StorageSlot::with_value(
+        "counter::counter_slot".parse()?,
+        Word::default(),
+    )];
+    let account = create_wallet_with_component(&mut client, &keystore, &counter_code, slots)
+        .await?
+        .account;
+
+    verify_contract_layout(&account, &["counter::counter_slot"])?;
+    assert!(keystore_has_key_for(&keystore, &account)?);
+    assert!(client.get_account(account.id()).await?.is_some());
+
+    std::fs::remove_dir_all(&keystore_dir)?;
+    Ok(())
+}
+
+#[test]
+fn only_stale_state_rejections_are_retried() {
+    let rejection = |endpoint, message: &str| {
+        ClientError::RpcError(RpcError::GrpcError {
+            endpoint,
+            error_kind: GrpcError::InvalidArgument,
+            source: Some(message.into()),
+        })
+    };
+
+    assert!(is_stale_account_error(&rejection(
+        NodeRpcClientEndpoint::SubmitProvenTx,
+        "initial account commitment does not match the current one",
+    )));
+    assert!(!is_stale_account_error(&rejection(
+        NodeRpcClientEndpoint::SubmitProvenTx,
+        "invalid proof",
+    )));
+    assert!(!is_stale_account_error(&rejection(
+        NodeRpcClientEndpoint::GetAccount,
+        "account nonce is too low",
+    )));
+    assert!(!is_stale_account_error(&ClientError::AccountNonceTooLow));
+}